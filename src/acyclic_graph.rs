@@ -0,0 +1,370 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::error::GraphError;
+use crate::graph::Graph;
+
+/// Counts the incoming connections of every node in the graph.
+fn in_degrees<'a, G, K, V>(graph: &'a G) -> HashMap<K, usize>
+where
+    G: Graph<'a, K, V> + ?Sized,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+    let mut degrees: HashMap<K, usize> = graph.node_keys().map(|key| (key, 0)).collect();
+    for source in graph.node_keys() {
+        for destination in graph.get_edges(&source).into_iter().flatten() {
+            *degrees.entry(*destination).or_insert(0) += 1;
+        }
+    }
+    degrees
+}
+
+/// The state of the circuit search in Johnson's algorithm, over node indices.
+struct CircuitSearch<'s> {
+    adjacency: &'s [Vec<usize>],
+    start: usize,
+    component: Vec<bool>,
+    blocked: Vec<bool>,
+    blocked_by: Vec<Vec<usize>>,
+    stack: Vec<usize>,
+    cycles: Vec<Vec<usize>>
+}
+
+impl CircuitSearch<'_> {
+    fn circuit(&mut self, node: usize) -> bool {
+        let mut found = false;
+        self.stack.push(node);
+        self.blocked[node] = true;
+
+        for &next in &self.adjacency[node] {
+            if !self.component[next] {
+                continue;
+            }
+            if next == self.start {
+                self.cycles.push(self.stack.clone());
+                found = true;
+            } else if !self.blocked[next] && self.circuit(next) {
+                found = true;
+            }
+        }
+
+        if found {
+            self.unblock(node);
+        } else {
+            for &next in &self.adjacency[node] {
+                if self.component[next] && !self.blocked_by[next].contains(&node) {
+                    self.blocked_by[next].push(node);
+                }
+            }
+        }
+        self.stack.pop();
+        found
+    }
+
+    fn unblock(&mut self, node: usize) {
+        self.blocked[node] = false;
+        while let Some(next) = self.blocked_by[node].pop() {
+            if self.blocked[next] {
+                self.unblock(next);
+            }
+        }
+    }
+}
+
+/// Marks the nodes with an index of at least `start` that are in the same strongly connected
+/// component as `start`, when only considering those nodes.
+fn start_component(adjacency: &[Vec<usize>], reverse: &[Vec<usize>], start: usize) -> Vec<bool> {
+    let flood = |edges: &[Vec<usize>]| {
+        let mut reached = vec![false; edges.len()];
+        let mut stack = vec![start];
+        reached[start] = true;
+        while let Some(node) = stack.pop() {
+            for &next in &edges[node] {
+                if next >= start && !reached[next] {
+                    reached[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        reached
+    };
+    let forward = flood(adjacency);
+    let backward = flood(reverse);
+    forward.iter().zip(backward).map(|(f, b)| *f && b).collect()
+}
+
+pub trait AcyclicGraph<'a, K, V>: Graph<'a, K, V>
+where
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+    /// Returns whether the graph contains a directed cycle, including self-loops.
+    fn has_cycle(&'a self) -> bool {
+        let mut finished: HashSet<K> = HashSet::new();
+        let mut on_stack: HashSet<K> = HashSet::new();
+
+        for start in self.node_keys() {
+            if finished.contains(&start) {
+                continue;
+            }
+            let mut stack: Vec<(K, Self::EdgeIterator)> = vec![];
+            if let Some(edges) = self.get_edges(&start) {
+                on_stack.insert(start);
+                stack.push((start, edges));
+            }
+
+            while let Some((node, edges)) = stack.last_mut() {
+                let node = *node;
+                match edges.next().copied() {
+                    Some(next) if on_stack.contains(&next) => return true,
+                    Some(next) if !finished.contains(&next) => {
+                        if let Some(next_edges) = self.get_edges(&next) {
+                            on_stack.insert(next);
+                            stack.push((next, next_edges));
+                        }
+                    }
+                    Some(_) => {}
+                    None => {
+                        on_stack.remove(&node);
+                        finished.insert(node);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Returns whether adding a connection between two nodes would create a cycle, i.e. whether
+    /// the source node is reachable from the destination node.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    fn would_create_cycle(&'a self, source: &K, destination: &K) -> bool {
+        let mut visited: HashSet<K> = HashSet::from([*destination]);
+        let mut stack: Vec<K> = vec![*destination];
+        while let Some(node) = stack.pop() {
+            if &node == source {
+                return true;
+            }
+            for edge in self.get_edges(&node).into_iter().flatten() {
+                if visited.insert(*edge) {
+                    stack.push(*edge);
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the keys of every node without incoming connections, in key order.
+    fn roots(&'a self) -> Vec<K> {
+        let degrees = in_degrees(self);
+        self.node_keys()
+            .filter(|key| degrees.get(key) == Some(&0))
+            .collect()
+    }
+
+    /// Returns whether the graph is a forest of rooted trees, i.e. it is acyclic and no node has
+    /// more than one incoming connection.
+    fn is_forest(&'a self) -> bool {
+        in_degrees(self).values().all(|degree| *degree <= 1) && !self.has_cycle()
+    }
+
+    /// Splits the nodes of the graph into layers, where every node only has incoming connections
+    /// from nodes in earlier layers. The first layer holds every node without incoming connections.
+    /// Returns `GraphError::Cycle` if the graph isn't acyclic.
+    fn topological_layers(&'a self) -> Result<Vec<Vec<K>>, GraphError> {
+        let mut degrees = in_degrees(self);
+        let mut layers: Vec<Vec<K>> = vec![];
+        let mut layer: Vec<K> = self.node_keys()
+            .filter(|key| degrees.get(key) == Some(&0))
+            .collect();
+        let mut layered = 0;
+
+        while !layer.is_empty() {
+            let mut next_layer: Vec<K> = vec![];
+            for node in &layer {
+                for edge in self.get_edges(node).into_iter().flatten() {
+                    if let Some(degree) = degrees.get_mut(edge) {
+                        *degree -= 1;
+                        if *degree == 0 {
+                            next_layer.push(*edge);
+                        }
+                    }
+                }
+            }
+            layered += layer.len();
+            layers.push(layer);
+            layer = next_layer;
+        }
+
+        if layered == degrees.len() {
+            Ok(layers)
+        } else {
+            Err(GraphError::Cycle)
+        }
+    }
+
+    /// Returns every elementary cycle in the graph, i.e. every closed path that doesn't repeat a
+    /// node, using Johnson's algorithm. Each cycle starts at its node that comes first in key order.
+    /// Note that a graph can have exponentially many elementary cycles, so this should only be
+    /// used on graphs known to have few cycles.
+    fn all_elementary_cycles(&'a self) -> Vec<Vec<K>> {
+        let keys: Vec<K> = self.node_keys().collect();
+        let indices: HashMap<K, usize> = keys.iter()
+            .enumerate()
+            .map(|(index, key)| (*key, index))
+            .collect();
+        let mut adjacency: Vec<Vec<usize>> = vec![vec![]; keys.len()];
+        let mut reverse: Vec<Vec<usize>> = vec![vec![]; keys.len()];
+        for (index, key) in keys.iter().enumerate() {
+            for edge in self.get_edges(key).into_iter().flatten() {
+                if let Some(&next) = indices.get(edge) {
+                    if !adjacency[index].contains(&next) {
+                        adjacency[index].push(next);
+                        reverse[next].push(index);
+                    }
+                }
+            }
+        }
+
+        let mut cycles: Vec<Vec<K>> = vec![];
+        for start in 0..keys.len() {
+            let mut search = CircuitSearch {
+                adjacency: &adjacency,
+                start,
+                component: start_component(&adjacency, &reverse, start),
+                blocked: vec![false; keys.len()],
+                blocked_by: vec![vec![]; keys.len()],
+                stack: vec![],
+                cycles: vec![]
+            };
+            search.circuit(start);
+            cycles.extend(
+                search.cycles
+                    .into_iter()
+                    .map(|cycle| cycle.into_iter().map(|index| keys[index]).collect())
+            );
+        }
+
+        cycles
+    }
+
+    /// Returns the number of layers in the topological layer decomposition of the graph, which is
+    /// the number of nodes on its longest path. Returns `GraphError::Cycle` if the graph isn't acyclic.
+    fn topological_depth(&'a self) -> Result<usize, GraphError> {
+        self.topological_layers().map(|layers| layers.len())
+    }
+}
+
+impl<'a, T, K, V> AcyclicGraph<'a, K, V> for T
+where
+    T: Graph<'a, K, V>,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adjacency_list_graph::AdjacencyListGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_roots_and_is_forest() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&3, &4);
+
+        assert_eq!(graph.roots(), vec![0, 3]);
+        assert!(graph.is_forest());
+
+        graph.add_connection(&3, &2);
+        assert!(!graph.is_forest());
+
+        graph.remove_connection(&3, &2);
+        graph.add_connection(&2, &0);
+        assert!(graph.has_cycle());
+        assert!(!graph.is_forest());
+        assert_eq!(graph.roots(), vec![3]);
+    }
+
+    #[test]
+    fn test_topological_depth() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+
+        assert_eq!(graph.topological_layers(), Ok(vec![vec![0], vec![1, 2], vec![3]]));
+        assert_eq!(graph.topological_depth(), Ok(3));
+
+        graph.add_connection(&3, &1);
+        assert_eq!(graph.topological_depth(), Err(GraphError::Cycle));
+    }
+
+    #[test]
+    fn test_all_elementary_cycles() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &0);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &2);
+        graph.add_connection(&1, &2);
+        assert_eq!(graph.all_elementary_cycles(), vec![vec![0, 1], vec![2, 3, 4]]);
+
+        // Two cycles sharing node 2, plus a self-loop.
+        graph.add_connection(&2, &4);
+        graph.add_connection(&3, &3);
+        assert_eq!(
+            graph.all_elementary_cycles(),
+            vec![vec![0, 1], vec![2, 3, 4], vec![2, 4], vec![3]]
+        );
+    }
+
+    #[test]
+    fn test_would_create_cycle() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+
+        assert!(graph.would_create_cycle(&2, &0));
+        assert!(graph.would_create_cycle(&1, &1));
+        assert!(!graph.would_create_cycle(&0, &2));
+    }
+}
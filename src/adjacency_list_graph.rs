@@ -1,52 +1,513 @@
 use num::traits::{PrimInt, Zero};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::acyclic_graph::AcyclicGraph;
+use crate::error::GraphError;
 use crate::graph::Graph;
+use crate::searchable_graph::SearchableGraph;
 use crate::weighted_graph::{NoWeight, WeightedGraph};
 
+/// A matrix indexed by node key, holding an optional entry for every pair of nodes.
+pub type KeyMatrix<T> = Vec<Vec<Option<T>>>;
+
 #[derive(Debug, Copy, Clone)]
-pub struct AdjacencyListEdge<K: Copy, W: Copy> {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AdjacencyListEdge<K: Copy, W: Copy, E = ()> {
     destination: K,
-    weight: W
+    weight: W,
+    payload: E
 }
 
 #[derive(Debug)]
-pub struct AdjacencyListGraph<K, V, W = NoWeight>
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "AdjacencyListGraphData<K, V, W, E>"))]
+pub struct AdjacencyListGraph<K, V, W = NoWeight, E = ()>
+where
+    K: PrimInt + Copy,
+    V: PartialEq,
+    W: PartialOrd + Zero + Copy
+{
+    // Removed nodes leave a `None` tombstone behind so that the keys of other nodes stay valid.
+    nodes: Vec<Option<V>>,
+    edges: Vec<Vec<AdjacencyListEdge<K, W, E>>>,
+    // Indices of tombstoned slots, reused by `insert` before the graph grows. It's rebuilt from
+    // the tombstones when deserializing, so it isn't serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    free: Vec<usize>
+}
+
+/// The serialized fields of an `AdjacencyListGraph`, which are checked before being turned into
+/// a graph so that a malformed payload can't break the invariants the graph relies on.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct AdjacencyListGraphData<K: Copy, V, W: Copy, E> {
+    nodes: Vec<Option<V>>,
+    edges: Vec<Vec<AdjacencyListEdge<K, W, E>>>
+}
+
+#[cfg(feature = "serde")]
+impl<K, V, W, E> TryFrom<AdjacencyListGraphData<K, V, W, E>> for AdjacencyListGraph<K, V, W, E>
 where
     K: PrimInt + Copy,
     V: PartialEq,
     W: PartialOrd + Zero + Copy
 {
-    nodes: Vec<V>,
-    edges: Vec<Vec<AdjacencyListEdge<K, W>>>
+    type Error = GraphError;
+
+    /// Returns `GraphError::InvalidKey` if there isn't exactly one row of connections per node or
+    /// a connection starts or ends at a node that doesn't exist, and
+    /// `GraphError::CapacityExceeded` if the key type can't represent the key of every node.
+    fn try_from(data: AdjacencyListGraphData<K, V, W, E>) -> Result<Self, GraphError> {
+        let AdjacencyListGraphData { nodes, edges } = data;
+        if edges.len() != nodes.len() {
+            return Err(GraphError::InvalidKey);
+        }
+        if !nodes.is_empty() && K::from(nodes.len() - 1).is_none() {
+            return Err(GraphError::CapacityExceeded);
+        }
+
+        let exists = |key: &K| {
+            key.to_usize().and_then(|index| nodes.get(index)).is_some_and(Option::is_some)
+        };
+        for (node, row) in nodes.iter().zip(&edges) {
+            // Removing a node drops every connection into and out of it.
+            let dangling = node.is_none() && !row.is_empty();
+            if dangling || !row.iter().all(|edge| exists(&edge.destination)) {
+                return Err(GraphError::InvalidKey);
+            }
+        }
+
+        let free = (0..nodes.len()).filter(|index| nodes[*index].is_none()).collect();
+        Ok(AdjacencyListGraph { nodes, edges, free })
+    }
 }
 
-impl<K, V, W> AdjacencyListGraph<K, V, W>
+impl<K, V, W, E> AdjacencyListGraph<K, V, W, E>
 where
     K: PrimInt + Copy,
     V: PartialEq,
     W: PartialOrd + Zero + Copy
 {
-    pub fn new(nodes: Vec<V>) -> AdjacencyListGraph<K, V, W> {
-        let edges = vec![vec![]; nodes.len()];
+    pub fn new(nodes: Vec<V>) -> AdjacencyListGraph<K, V, W, E> {
+        let edges = std::iter::repeat_with(Vec::new).take(nodes.len()).collect();
+        AdjacencyListGraph {
+            nodes: nodes.into_iter().map(Some).collect(),
+            edges,
+            free: vec![]
+        }
+    }
+
+    /// Creates a graph from its node values and a list of `(source, destination, weight)`
+    /// connections between node indices, returning the graph along with the number of
+    /// connections that were skipped because an endpoint isn't a node in the graph.
+    /// # Arguments
+    /// * `nodes` - the values of the nodes in the graph.
+    /// * `edges` - the connections between the nodes, by index.
+    pub fn from_nodes_and_edges(
+        nodes: Vec<V>,
+        edges: Vec<(usize, usize, W)>
+    ) -> (AdjacencyListGraph<K, V, W, E>, usize)
+    where
+        E: Default
+    {
+        let mut graph = AdjacencyListGraph::new(nodes);
+        let mut skipped = 0;
+        for (source, destination, weight) in edges {
+            let endpoints = (K::from(source), K::from(destination));
+            let (Some(source), Some(destination)) = endpoints else {
+                skipped += 1;
+                continue;
+            };
+            if !graph.add_edge_with_payload(&source, &destination, weight, E::default()) {
+                skipped += 1;
+            }
+        }
+        (graph, skipped)
+    }
+
+    /// Creates a path graph, connecting each node to the next one in order.
+    /// # Arguments
+    /// * `nodes` - the values of the nodes along the path.
+    pub fn path(nodes: Vec<V>) -> AdjacencyListGraph<K, V, W, E>
+    where
+        E: Default
+    {
+        let edges = (1..nodes.len()).map(|index| (index - 1, index, W::zero())).collect();
+        AdjacencyListGraph::from_nodes_and_edges(nodes, edges).0
+    }
+
+    /// Creates a cycle graph, connecting each node to the next one in order and the last node
+    /// back to the first.
+    /// # Arguments
+    /// * `nodes` - the values of the nodes around the cycle.
+    pub fn cycle(nodes: Vec<V>) -> AdjacencyListGraph<K, V, W, E>
+    where
+        E: Default
+    {
+        let last = nodes.len().checked_sub(1);
+        let mut graph = AdjacencyListGraph::path(nodes);
+        if let Some((last, first)) = last.and_then(K::from).zip(K::from(0)) {
+            graph.add_edge_with_payload(&last, &first, W::zero(), E::default());
+        }
+        graph
+    }
+
+    /// Adds a weighted connection between two nodes in the graph that carries a payload.
+    /// Returns `false` if either key doesn't refer to a node in the graph.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    /// * `weight` - the weight of the connection.
+    /// * `payload` - the payload of the connection.
+    pub fn add_edge_with_payload(
+        &mut self,
+        source: &K,
+        destination: &K,
+        weight: W,
+        payload: E
+    ) -> bool {
+        if self.edge_row(destination).is_none() {
+            return false;
+        }
+        let Some(edges) = self.edge_row_mut(source) else {
+            return false;
+        };
+        let edge = AdjacencyListEdge {
+            destination: *destination,
+            weight,
+            payload
+        };
+        edges.push(edge);
+        true
+    }
+
+    /// Adds a connection between two nodes in the graph, unless it would create a cycle.
+    /// Returns whether the connection was added.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    pub fn add_connection_acyclic(&mut self, source: &K, destination: &K) -> bool
+    where
+        K: Hash,
+        E: Default
+    {
+        !self.would_create_cycle(source, destination) && self.add_connection(source, destination)
+    }
+
+    /// Returns the payload of the first connection between two nodes in the graph, if it exists,
+    /// otherwise `None`.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    pub fn get_edge_payload(&self, source: &K, destination: &K) -> Option<&E> {
+        self.edge_row(source)?
+            .iter()
+            .find(|e| &e.destination == destination)
+            .map(|e| &e.payload)
+    }
+
+    /// Returns the index of the first connection between two nodes within the source node's
+    /// connections, if it exists, otherwise `None`.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    pub fn edge_position(&self, source: &K, destination: &K) -> Option<usize> {
+        self.edge_row(source)?.iter().position(|e| &e.destination == destination)
+    }
+
+    /// Swaps the keys of two nodes in the graph, so that each node's value and its incoming and
+    /// outgoing connections move to the other node's key. Returns `false` if either key isn't a
+    /// node in the graph.
+    /// # Arguments
+    /// * `a` - the key of the first node to swap.
+    /// * `b` - the key of the second node to swap.
+    pub fn swap_nodes(&mut self, a: &K, b: &K) -> bool {
+        if self.edge_row(a).is_none() || self.edge_row(b).is_none() {
+            return false;
+        }
+        let (Some(i), Some(j)) = (a.to_usize(), b.to_usize()) else {
+            return false;
+        };
+
+        self.nodes.swap(i, j);
+        self.edges.swap(i, j);
+        for edge in self.edges.iter_mut().flatten() {
+            if &edge.destination == a {
+                edge.destination = *b;
+            } else if &edge.destination == b {
+                edge.destination = *a;
+            }
+        }
+        true
+    }
+
+    /// Reverses the direction of every connection in the graph, keeping their weights and
+    /// payloads.
+    pub fn transpose_in_place(&mut self) {
+        let mut transposed: Vec<Vec<AdjacencyListEdge<K, W, E>>> =
+            std::iter::repeat_with(Vec::new).take(self.edges.len()).collect();
+        for (index, edges) in std::mem::take(&mut self.edges).into_iter().enumerate() {
+            for edge in edges {
+                let Some(row) = edge.destination.to_usize().and_then(|i| transposed.get_mut(i)) else {
+                    continue;
+                };
+                row.push(AdjacencyListEdge {
+                    destination: K::from(index).unwrap(),
+                    weight: edge.weight,
+                    payload: edge.payload
+                });
+            }
+        }
+        self.edges = transposed;
+    }
+
+    /// Returns a copy of the graph with the direction of every connection reversed, keeping the
+    /// node values, keys, weights and payloads.
+    pub fn transpose(&self) -> AdjacencyListGraph<K, V, W, E>
+    where
+        V: Clone,
+        E: Clone
+    {
+        let mut transposed = AdjacencyListGraph {
+            nodes: self.nodes.clone(),
+            edges: self.edges.clone(),
+            free: self.free.clone()
+        };
+        transposed.transpose_in_place();
+        transposed
+    }
+
+    /// Returns the shortest-path DAG from a node in the graph: a copy with the same nodes and
+    /// keys that only keeps the connections `(u, v)` where `dist(u) + w(u, v) == dist(v)`, i.e.
+    /// every connection lying on some shortest path from the source. Distances are found with
+    /// Dijkstra's algorithm, so weights must not be negative. Edge payloads are dropped.
+    /// # Arguments
+    /// * `source` - the key of the node every path starts at.
+    pub fn shortest_path_dag(&self, source: &K) -> AdjacencyListGraph<K, V, W>
+    where
+        K: Hash,
+        V: Clone,
+        E: Default
+    {
+        let distances: HashMap<K, W> = self.reachable_sorted_by_cost(source).into_iter().collect();
+        let mut edges: Vec<Vec<AdjacencyListEdge<K, W>>> =
+            std::iter::repeat_with(Vec::new).take(self.edges.len()).collect();
+        for (index, row) in self.edges.iter().enumerate() {
+            let Some(start) = distances.get(&K::from(index).unwrap()) else {
+                continue;
+            };
+            for edge in row {
+                if distances.get(&edge.destination) == Some(&(*start + edge.weight)) {
+                    edges[index].push(AdjacencyListEdge {
+                        destination: edge.destination,
+                        weight: edge.weight,
+                        payload: ()
+                    });
+                }
+            }
+        }
         AdjacencyListGraph {
-            nodes,
-            edges
+            nodes: self.nodes.clone(),
+            edges,
+            free: self.free.clone()
+        }
+    }
+
+    /// Removes every node that isn't reachable from a root node in the graph, returning the
+    /// number of nodes removed. The keys of the remaining nodes are unchanged.
+    /// # Arguments
+    /// * `root` - the key of the node to keep every reachable node from.
+    pub fn trim_to_reachable(&mut self, root: &K) -> usize
+    where
+        K: Hash,
+        E: Default
+    {
+        if self.get_value(root).is_none() {
+            return 0;
+        }
+        let mut reachable = self.descendants(root);
+        reachable.insert(*root);
+        let unreachable: Vec<K> = self.node_keys().filter(|key| !reachable.contains(key)).collect();
+        for key in &unreachable {
+            self.remove(key);
+        }
+        unreachable.len()
+    }
+
+    /// Returns the weight of the shortest path between every pair of nodes, computed with the
+    /// Floyd-Warshall algorithm. The matrix is indexed by node key, with `None` where there is no
+    /// path and zero on the diagonal. Slots of removed nodes have no paths at all, not even to
+    /// themselves. Negative weights are supported, but the result is meaningless if the graph
+    /// contains a negative-weight cycle; use `try_all_pairs_shortest_paths` to detect one.
+    pub fn all_pairs_shortest_paths(&self) -> KeyMatrix<W> {
+        self.all_pairs_shortest_paths_with_next().0
+    }
+
+    /// Returns the same matrix as `all_pairs_shortest_paths`, or `GraphError::NegativeCycle` if
+    /// the graph contains a negative-weight cycle, found as a node whose shortest path back to
+    /// itself is negative.
+    pub fn try_all_pairs_shortest_paths(&self) -> Result<KeyMatrix<W>, GraphError> {
+        let distances = self.all_pairs_shortest_paths();
+        let negative = distances.iter()
+            .enumerate()
+            .any(|(i, row)| row[i].is_some_and(|distance| distance < W::zero()));
+        if negative {
+            Err(GraphError::NegativeCycle)
+        } else {
+            Ok(distances)
+        }
+    }
+
+    /// Returns the same matrix as `all_pairs_shortest_paths`, along with a matrix holding the key
+    /// of the next node after `i` on a shortest path from `i` to `j`, which can be followed to
+    /// reconstruct the path. The next node from a node to itself is the node itself.
+    pub fn all_pairs_shortest_paths_with_next(&self) -> (KeyMatrix<W>, KeyMatrix<K>) {
+        let size = self.nodes.len();
+        let mut distances: KeyMatrix<W> = vec![vec![None; size]; size];
+        let mut next: KeyMatrix<K> = vec![vec![None; size]; size];
+
+        for (i, edges) in self.edges.iter().enumerate() {
+            if self.nodes[i].is_none() {
+                continue;
+            }
+            distances[i][i] = Some(W::zero());
+            next[i][i] = K::from(i);
+            for edge in edges {
+                let Some(j) = edge.destination.to_usize() else {
+                    continue;
+                };
+                if self.nodes.get(j).is_none_or(Option::is_none) {
+                    continue;
+                }
+                if distances[i][j].is_none_or(|current| edge.weight < current) {
+                    distances[i][j] = Some(edge.weight);
+                    next[i][j] = Some(edge.destination);
+                }
+            }
+        }
+
+        for k in 0..size {
+            for i in 0..size {
+                let Some(to_k) = distances[i][k] else {
+                    continue;
+                };
+                for j in 0..size {
+                    let Some(from_k) = distances[k][j] else {
+                        continue;
+                    };
+                    let candidate = to_k + from_k;
+                    if distances[i][j].is_none_or(|current| candidate < current) {
+                        distances[i][j] = Some(candidate);
+                        next[i][j] = next[i][k];
+                    }
+                }
+            }
+        }
+
+        (distances, next)
+    }
+
+    /// Merges every group of nodes with equal values into the group's first node in key order,
+    /// redirecting the connections into and out of the other nodes to it and then removing them.
+    /// Redirected connections that become self-loops, or that duplicate another connection of
+    /// the same node, are dropped. Returns the number of nodes removed.
+    pub fn deduplicate_by_value(&mut self) -> usize
+    where
+        K: Hash,
+        E: Default
+    {
+        let mut merged: HashMap<K, K> = HashMap::new();
+        let mut moved: HashMap<usize, Vec<AdjacencyListEdge<K, W, E>>> = HashMap::new();
+        for group in self.duplicate_value_groups() {
+            for key in &group[1..] {
+                merged.insert(*key, group[0]);
+                let (Some(from), Some(to)) = (key.to_usize(), group[0].to_usize()) else {
+                    continue;
+                };
+                let row = std::mem::take(&mut self.edges[from]);
+                moved.entry(to).or_default().extend(row);
+            }
+        }
+        if merged.is_empty() {
+            return 0;
+        }
+
+        for (index, row) in self.edges.iter_mut().enumerate() {
+            let source = K::from(index).unwrap();
+            let mut redirected = moved.remove(&index).unwrap_or_default();
+            let mut edges: Vec<AdjacencyListEdge<K, W, E>> = vec![];
+            for edge in std::mem::take(row) {
+                if merged.contains_key(&edge.destination) {
+                    redirected.push(edge);
+                } else {
+                    edges.push(edge);
+                }
+            }
+            for edge in redirected.iter_mut() {
+                if let Some(kept) = merged.get(&edge.destination) {
+                    edge.destination = *kept;
+                }
+            }
+            // Redirected connections are only kept if they're neither self-loops nor duplicates.
+            let mut seen: HashSet<K> = edges.iter().map(|edge| edge.destination).collect();
+            seen.insert(source);
+            edges.extend(redirected.into_iter().filter(|edge| seen.insert(edge.destination)));
+            *row = edges;
+        }
+
+        // Removing in key order keeps the keys handed out by later inserts deterministic.
+        let mut removed: Vec<K> = merged.into_keys().collect();
+        removed.sort();
+        for key in &removed {
+            self.remove(key);
+        }
+        removed.len()
+    }
+
+    fn edge_row(&self, key: &K) -> Option<&Vec<AdjacencyListEdge<K, W, E>>> {
+        let index = key.to_usize()?;
+        self.nodes.get(index)?.as_ref()?;
+        self.edges.get(index)
+    }
+
+    fn edge_row_mut(&mut self, key: &K) -> Option<&mut Vec<AdjacencyListEdge<K, W, E>>> {
+        let index = key.to_usize()?;
+        self.nodes.get(index)?.as_ref()?;
+        self.edges.get_mut(index)
+    }
+
+    /// Grows the graph with default-valued nodes until a key refers to a node in it.
+    fn grow_to(&mut self, key: &K)
+    where
+        V: Default
+    {
+        let Some(len) = key.to_usize().map(|index| index + 1) else {
+            return;
+        };
+        if len > self.nodes.len() {
+            self.nodes.resize_with(len, || Some(V::default()));
+            self.edges.resize_with(len, Vec::new);
         }
     }
 }
 
-pub struct EdgeDestinationIterator<'a, K, W>
+pub struct EdgeDestinationIterator<'a, K, W, E = ()>
 where
     K: Copy,
     W: Copy
 {
-    iter: std::slice::Iter<'a, AdjacencyListEdge<K, W>>,
+    iter: std::slice::Iter<'a, AdjacencyListEdge<K, W, E>>,
 }
 
-impl<'a, K, W> Iterator for EdgeDestinationIterator<'a, K, W>
+impl<'a, K, W, E> Iterator for EdgeDestinationIterator<'a, K, W, E>
 where 
     K: Copy,
-    W: Copy + 'a
+    W: Copy + 'a,
+    E: 'a
 {
     type Item = &'a K;
 
@@ -55,38 +516,65 @@ where
     }
 }
 
-impl<'a, K, W> DoubleEndedIterator for EdgeDestinationIterator<'a, K, W>
+impl<'a, K, W, E> DoubleEndedIterator for EdgeDestinationIterator<'a, K, W, E>
 where 
     K: Copy,
-    W: Copy + 'a
+    W: Copy + 'a,
+    E: 'a
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.iter.next_back().map(|e| &e.destination)
     }
 }
 
-impl<'a, K, V, W> Graph<'a, K, V> for AdjacencyListGraph<K, V, W>
+pub struct NodeKeyIterator<'a, K, V> {
+    iter: std::iter::Enumerate<std::slice::Iter<'a, Option<V>>>,
+    _key: std::marker::PhantomData<K>,
+}
+
+impl<'a, K, V> Iterator for NodeKeyIterator<'a, K, V>
+where
+    K: PrimInt
+{
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .find(|(_, node)| node.is_some())
+            .map(|(index, _)| K::from(index).unwrap())
+    }
+}
+
+impl<'a, K, V, W, E> Graph<'a, K, V> for AdjacencyListGraph<K, V, W, E>
 where
     K: PrimInt + Copy + 'a,
     V: PartialEq + 'a,
-    W: PartialOrd + Zero + Copy + 'a
+    W: PartialOrd + Zero + Copy + 'a,
+    E: Default + 'a
 {
-    type EdgeIterator = EdgeDestinationIterator<'a, K, W>;
+    type EdgeIterator = EdgeDestinationIterator<'a, K, W, E>;
+    type NodeKeyIterator = NodeKeyIterator<'a, K, V>;
+
+    fn try_insert(&mut self, value: V) -> Result<K, GraphError> {
+        if let Some(index) = self.free.pop() {
+            self.nodes[index] = Some(value);
+            return Ok(K::from(index).unwrap());
+        }
 
-    fn insert(&mut self, value: V) -> K {
-        self.nodes.push(value);
+        let key = K::from(self.nodes.len()).ok_or(GraphError::CapacityExceeded)?;
+        self.nodes.push(Some(value));
         self.edges.push(vec![]);
-        K::from(self.nodes.len() - 1).unwrap()
+        Ok(key)
     }
 
     fn remove(&mut self, key: &K) -> Option<V> {
         let index = key.to_usize()?;
-        if index >= self.nodes.len() {
-            return None;
-        }
+        let value = self.nodes.get_mut(index)?.take()?;
 
-        self.edges.remove(index);
-        Some(self.nodes.remove(index))
+        self.edges[index].clear();
+        self.remove_incoming(key);
+        self.free.push(index);
+        Some(value)
     }
 
     fn add_connection(
@@ -94,16 +582,7 @@ where
         source: &K,
         destination: &K
     ) -> bool {
-        let index = source.to_usize().unwrap();
-        let Some(edges) = self.edges.get_mut(index) else {
-            return false;
-        };
-        let edge = AdjacencyListEdge {
-            destination: destination.clone(),
-            weight: W::zero()
-        };
-        edges.push(edge);
-        true
+        self.add_edge_with_payload(source, destination, W::zero(), E::default())
     }
 
     fn remove_connection(
@@ -111,8 +590,7 @@ where
         source: &K,
         destination: &K
     ) -> bool {
-        let index = source.to_usize().unwrap();
-        let Some(edges) = self.edges.get_mut(index) else {
+        let Some(edges) = self.edge_row_mut(source) else {
             return false;
         };
         if let Some(index) = edges.iter().position(|e| &e.destination == destination) {
@@ -123,9 +601,23 @@ where
         }
     }
 
-    fn get(&'a self, key: &K) -> Option<(&V, Self::EdgeIterator)> {
-        let Some(node) = self.nodes.get(key.to_usize()?) else { return None; } ;
-        let Some(edges) = self.edges.get(key.to_usize()?) else { return None; };
+    fn remove_incoming(&mut self, key: &K) -> usize {
+        let mut removed = 0;
+        for edges in self.edges.iter_mut() {
+            let before = edges.len();
+            edges.retain(|e| &e.destination != key);
+            removed += before - edges.len();
+        }
+        removed
+    }
+
+    fn remove_outgoing(&mut self, key: &K) -> usize {
+        self.edge_row_mut(key).map_or(0, |edges| std::mem::take(edges).len())
+    }
+
+    fn get(&'a self, key: &K) -> Option<(&'a V, Self::EdgeIterator)> {
+        let node = self.get_value(key)?;
+        let edges = self.edge_row(key)?;
         let destination_iter = EdgeDestinationIterator {
             iter: edges.iter()
         };
@@ -133,30 +625,46 @@ where
     }
 
     fn get_value(&self, key: &K) -> Option<&V> {
-        self.nodes.get(key.to_usize()?)
+        self.nodes.get(key.to_usize()?)?.as_ref()
     }
 
     fn get_edges(&'a self, key: &K) -> Option<Self::EdgeIterator> {
-        let edges = self.edges.get(key.to_usize()?)?;
+        let edges = self.edge_row(key)?;
         let destination_iter = EdgeDestinationIterator {
             iter: edges.iter()
         };
         Some(destination_iter)
     }
+
+    fn node_count(&'a self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    fn edge_count(&'a self) -> usize {
+        self.edges.iter().map(Vec::len).sum()
+    }
+
+    fn node_keys(&'a self) -> Self::NodeKeyIterator {
+        NodeKeyIterator {
+            iter: self.nodes.iter().enumerate(),
+            _key: std::marker::PhantomData
+        }
+    }
 }
 
-pub struct WeightedEdgeIterator<'a, K, W>
+pub struct WeightedEdgeIterator<'a, K, W, E = ()>
 where
     K: Copy,
     W: Copy
 {
-    iter: std::slice::Iter<'a, AdjacencyListEdge<K, W>>,
+    iter: std::slice::Iter<'a, AdjacencyListEdge<K, W, E>>,
 }
 
-impl<'a, K, W> Iterator for WeightedEdgeIterator<'a, K, W>
+impl<'a, K, W, E> Iterator for WeightedEdgeIterator<'a, K, W, E>
 where 
     K: Copy,
-    W: Copy + 'a
+    W: Copy + 'a,
+    E: 'a
 {
     type Item = (&'a K, &'a W);
 
@@ -165,23 +673,25 @@ where
     }
 }
 
-impl<'a, K, W> DoubleEndedIterator for WeightedEdgeIterator<'a, K, W>
+impl<'a, K, W, E> DoubleEndedIterator for WeightedEdgeIterator<'a, K, W, E>
 where
     K: Copy,
-    W: Copy + 'a
+    W: Copy + 'a,
+    E: 'a
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.iter.next_back().map(|e| (&e.destination, &e.weight))
     }
 }
 
-impl<'a, K, V, W> WeightedGraph<'a, K, V, W> for AdjacencyListGraph<K, V, W> 
+impl<'a, K, V, W, E> WeightedGraph<'a, K, V, W> for AdjacencyListGraph<K, V, W, E> 
 where
     K: PrimInt + Copy + 'a,
     V: PartialEq + 'a,
-    W: PartialOrd + Zero + Copy + 'a
+    W: PartialOrd + Zero + Copy + 'a,
+    E: Default + 'a
 {
-    type WeightedEdgeIterator = WeightedEdgeIterator<'a, K, W>;
+    type WeightedEdgeIterator = WeightedEdgeIterator<'a, K, W, E>;
 
     fn add_weighted_connection(
         &mut self, 
@@ -189,21 +699,28 @@ where
         destination: &K, 
         weight: W
     ) -> bool {
-        let index = source.to_usize().unwrap();
-        let Some(edges) = self.edges.get_mut(index) else {
+        self.add_edge_with_payload(source, destination, weight, E::default())
+    }
+
+    fn remove_weighted_connection(&mut self, source: &K, destination: &K, weight: &W) -> bool
+    where
+        W: PartialEq
+    {
+        let Some(edges) = self.edge_row_mut(source) else {
             return false;
         };
-        let edge = AdjacencyListEdge {
-            destination: destination.clone(),
-            weight
-        };
-        edges.push(edge);
-        true
+        if let Some(index) = edges.iter()
+            .position(|e| &e.destination == destination && &e.weight == weight) {
+            edges.remove(index);
+            true
+        } else {
+            false
+        }
     }
 
-    fn get_weighted(&'a self, key: &K) -> Option<(&V, Self::WeightedEdgeIterator)> {
-        let Some(node) = self.nodes.get(key.to_usize()?) else { return None; } ;
-        let Some(edges) = self.edges.get(key.to_usize()?) else { return None; };
+    fn get_weighted(&'a self, key: &K) -> Option<(&'a V, Self::WeightedEdgeIterator)> {
+        let node = self.get_value(key)?;
+        let edges = self.edge_row(key)?;
         let edges_iter = WeightedEdgeIterator {
             iter: edges.iter()
         };
@@ -211,7 +728,7 @@ where
     }
 
     fn get_weighted_edges(&'a self, key: &K) -> Option<Self::WeightedEdgeIterator> {
-        let edges = self.edges.get(key.to_usize()?)?;
+        let edges = self.edge_row(key)?;
         let edges_iter = WeightedEdgeIterator {
             iter: edges.iter()
         };
@@ -219,9 +736,43 @@ where
     }
 }
 
+/// Creates a graph from `(source, destination, weight)` connections, adding default-valued nodes
+/// up to the highest key seen.
+impl<K, V, W, E> FromIterator<(K, K, W)> for AdjacencyListGraph<K, V, W, E>
+where
+    K: PrimInt + Copy,
+    V: PartialEq + Default,
+    W: PartialOrd + Zero + Copy,
+    E: Default
+{
+    fn from_iter<I: IntoIterator<Item = (K, K, W)>>(iter: I) -> Self {
+        let mut graph = AdjacencyListGraph::new(vec![]);
+        for (source, destination, weight) in iter {
+            graph.grow_to(&source.max(destination));
+            graph.add_edge_with_payload(&source, &destination, weight, E::default());
+        }
+        graph
+    }
+}
+
+/// Creates a graph from `(source, destination)` connections with zero weights, adding
+/// default-valued nodes up to the highest key seen.
+impl<K, V, W, E> FromIterator<(K, K)> for AdjacencyListGraph<K, V, W, E>
+where
+    K: PrimInt + Copy,
+    V: PartialEq + Default,
+    W: PartialOrd + Zero + Copy,
+    E: Default
+{
+    fn from_iter<I: IntoIterator<Item = (K, K)>>(iter: I) -> Self {
+        iter.into_iter()
+            .map(|(source, destination)| (source, destination, W::zero()))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::searchable_graph::SearchableGraph;
 
     use super::*;
 
@@ -329,6 +880,948 @@ mod tests {
         assert!(edges_5.eq(empty));
     }
 
+    #[test]
+    fn test_try_insert_capacity() {
+        let mut graph: AdjacencyListGraph<u8, usize> = AdjacencyListGraph::new((0..256).collect());
+        assert_eq!(graph.try_insert(256), Err(GraphError::CapacityExceeded));
+        assert_eq!(graph.node_count(), 256);
+
+        graph.remove(&7);
+        assert_eq!(graph.try_insert(256), Ok(7));
+    }
+
+    #[test]
+    fn test_insert_reuses_removed_slots() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&1, &2);
+
+        assert_eq!(graph.remove(&1), Some(String::from("node-2")));
+        assert_eq!(graph.get_value(&1), None);
+        assert!(graph.get_edges(&1).is_none());
+        assert!(graph.node_keys().eq(vec![0, 2]));
+        assert_eq!(graph.remove(&1), None);
+
+        let key = graph.insert(String::from("node-4"));
+        assert_eq!(key, 1);
+        assert_eq!(graph.get_value(&1), Some(&String::from("node-4")));
+        assert!(graph.get_edges(&1).unwrap().eq(vec![] as Vec<&u16>));
+
+        let key = graph.insert(String::from("node-5"));
+        assert_eq!(key, 3);
+    }
+
+    #[test]
+    fn test_node_keys() {
+        let empty: AdjacencyListGraph<u8, String> = AdjacencyListGraph::new(vec![]);
+        assert_eq!(empty.node_keys().count(), 0);
+
+        let mut graph: AdjacencyListGraph<u8, String> = AdjacencyListGraph::new(
+            (1..=5).map(|index| format!("node-{}", index)).collect()
+        );
+        assert!(graph.node_keys().eq(0..5));
+
+        graph.remove(&0);
+        graph.remove(&3);
+        assert!(graph.node_keys().eq(vec![1, 2, 4]));
+        assert!(graph.node_keys().all(|key| graph.get_value(&key).is_some()));
+
+        graph.insert(String::from("node-6"));
+        assert!(graph.node_keys().eq(vec![1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_remove_keeps_keys_stable() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=6).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&4, &5);
+        graph.add_connection(&5, &3);
+
+        graph.remove(&2);
+        assert_eq!(graph.get_value(&5), Some(&String::from("node-6")));
+        assert_eq!(graph.get_value(&4), Some(&String::from("node-5")));
+        assert!(graph.get_edges(&4).unwrap().eq(vec![&5]));
+        assert!(graph.get_edges(&5).unwrap().eq(vec![&3]));
+        assert!(graph.node_keys().eq(vec![0, 1, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_remove_purges_incoming_connections() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=4).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&3, &2);
+        graph.add_connection(&2, &2);
+
+        graph.remove(&2);
+        assert!(graph.node_keys().all(|key| graph.get_edges(&key).unwrap().all(|edge| *edge != 2)));
+        assert!(graph.get_edges(&1).unwrap().eq(vec![&3]));
+        assert_eq!(graph.find_path_bfs(&0, &2), None);
+    }
+
+    #[test]
+    fn test_add_connection_validates_destination() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+            ]
+        );
+        graph.remove(&1);
+
+        assert!(!graph.add_connection(&0, &999));
+        assert!(!graph.add_weighted_connection(&0, &999, 1));
+        assert!(!graph.add_connection(&0, &1));
+        assert_eq!(graph.get_edges(&0).unwrap().count(), 0);
+        assert!(graph.add_connection(&0, &0));
+    }
+
+    #[test]
+    fn test_node_and_edge_count() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=4).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &3);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+
+        graph.remove(&2);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        graph.insert(String::from("node-5"));
+        assert_eq!(graph.node_count(), 4);
+    }
+
+    #[test]
+    fn test_connection_count() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&0, &1);
+
+        assert_eq!(graph.connection_count(&0, &1), 3);
+        assert_eq!(graph.connection_count(&0, &2), 1);
+        assert_eq!(graph.connection_count(&1, &0), 0);
+        assert_eq!(graph.connection_count(&7, &0), 0);
+    }
+
+    #[test]
+    fn test_adjacency() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&0, &3);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &3);
+
+        let adjacency: Vec<(u16, Vec<u16>)> = graph.adjacency()
+            .map(|(key, edges)| (key, edges.copied().collect()))
+            .collect();
+        assert_eq!(adjacency.len(), 4);
+        assert_eq!(adjacency[1], (1, vec![2]));
+        assert_eq!(adjacency[0], (0, vec![1, 2, 3]));
+        assert_eq!(adjacency[3], (3, vec![]));
+    }
+
+    #[test]
+    fn test_remove_incoming() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&3, &2);
+        graph.add_connection(&3, &2);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&0, &1);
+
+        assert_eq!(graph.remove_incoming(&2), 4);
+        let in_degree: usize = graph.node_keys().map(|key| graph.connection_count(&key, &2)).sum();
+        assert_eq!(in_degree, 0);
+        assert!(graph.get_edges(&2).unwrap().eq(vec![&3]));
+        assert!(graph.get_edges(&0).unwrap().eq(vec![&1]));
+        assert_eq!(graph.remove_incoming(&2), 0);
+    }
+
+    #[test]
+    fn test_remove_outgoing() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &0);
+        graph.add_connection(&2, &0);
+
+        assert_eq!(graph.remove_outgoing(&0), 2);
+        assert_eq!(graph.get_edges(&0).unwrap().count(), 0);
+        assert!(graph.get_edges(&1).unwrap().eq(vec![&0]));
+        assert!(graph.get_edges(&2).unwrap().eq(vec![&0]));
+        assert_eq!(graph.remove_outgoing(&0), 0);
+        assert_eq!(graph.remove_outgoing(&9), 0);
+    }
+
+    #[test]
+    fn test_duplicate_value_groups() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-1"),
+                String::from("node-3"),
+            ]
+        );
+        assert_eq!(graph.duplicate_value_groups(), vec![vec![0, 2]]);
+
+        graph.remove(&2);
+        assert!(graph.duplicate_value_groups().is_empty());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let nodes = vec![
+            String::from("node-1"),
+            String::from("node-2"),
+            String::from("node-3"),
+        ];
+        let mut first: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(nodes.clone());
+        first.add_connection(&0, &1);
+        first.add_connection(&0, &2);
+        first.add_connection(&1, &2);
+        let mut second: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(nodes);
+        second.add_connection(&1, &2);
+        second.add_connection(&0, &2);
+        second.add_connection(&0, &1);
+        assert_eq!(first.fingerprint(), second.fingerprint());
+        // The hash is fixed, so a fingerprint can be cached across builds.
+        assert_eq!(first.fingerprint(), 0x8faf_3527_2132_0b9f);
+
+        second.remove_connection(&1, &2);
+        second.add_connection(&2, &1);
+        assert_ne!(first.fingerprint(), second.fingerprint());
+    }
+
+    #[test]
+    fn test_incident_edges() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &2);
+        graph.add_connection(&2, &1);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &2);
+        graph.add_connection(&4, &2);
+        graph.add_connection(&4, &2);
+
+        assert_eq!(graph.predecessors(&2), vec![0, 3, 4]);
+        let incident: Vec<u16> = graph.incident_edges(&2).collect();
+        assert_eq!(incident, vec![1, 3, 0, 4]);
+        assert_eq!(graph.incident_edges(&9).count(), 0);
+    }
+
+    #[test]
+    fn test_is_valid_path() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&0, &3);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &3);
+
+        assert!(graph.is_valid_path(&[0, 1, 2, 3]));
+        assert!(!graph.is_valid_path(&[0, 1, 3]));
+        assert!(!graph.is_valid_path(&[0, 1, 7]));
+        assert!(graph.is_valid_path(&[]));
+        assert!(graph.is_valid_path(&[2]));
+        assert!(!graph.is_valid_path(&[7]));
+    }
+
+    #[test]
+    fn test_try_get_edges() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+
+        assert!(graph.try_get_edges(&0).unwrap().eq(vec![&1]));
+        assert!(matches!(graph.try_get_edges(&2), Err(GraphError::InvalidKey)));
+        graph.remove(&1);
+        assert!(matches!(graph.try_get_edges(&1), Err(GraphError::InvalidKey)));
+    }
+
+    #[test]
+    fn test_is_simple() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &0);
+        graph.add_connection(&1, &2);
+        assert!(graph.is_simple());
+
+        graph.add_connection(&2, &2);
+        assert!(graph.has_self_loops());
+        assert!(!graph.has_parallel_edges());
+        assert!(!graph.is_simple());
+
+        graph.remove_connection(&2, &2);
+        graph.add_connection(&1, &2);
+        assert!(!graph.has_self_loops());
+        assert!(graph.has_parallel_edges());
+        assert!(!graph.is_simple());
+    }
+
+    #[test]
+    fn test_to_adjacency_vec() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&0, &3);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &3);
+
+        let manual: Vec<Vec<u16>> = (0..4)
+            .map(|key| graph.get_edges(&key).unwrap().copied().collect())
+            .collect();
+        assert_eq!(graph.to_adjacency_vec(), manual);
+        assert_eq!(graph.to_adjacency_vec(), vec![vec![1, 2, 3], vec![2], vec![3], vec![]]);
+    }
+
+    #[test]
+    fn test_degrees() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=4).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&2, &1);
+        graph.add_connection(&1, &1);
+
+        assert_eq!(graph.out_degree(&0), Some(2));
+        assert_eq!(graph.in_degree(&0), Some(0));
+        assert_eq!(graph.out_degree(&1), Some(1));
+        assert_eq!(graph.in_degree(&1), Some(3));
+        assert_eq!(graph.degree(&1), Some(4));
+        assert_eq!(graph.degree(&3), Some(0));
+        assert_eq!(graph.out_degree(&4), None);
+        assert_eq!(graph.in_degree(&4), None);
+        assert_eq!(graph.degree(&4), None);
+    }
+
+    #[test]
+    fn test_undirected_degree() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&2, &1);
+        graph.add_connection(&1, &1);
+
+        assert_eq!(graph.undirected_degree(&1), Some(4));
+        assert_eq!(graph.undirected_degree(&0), Some(1));
+        assert_eq!(graph.undirected_degree(&5), None);
+
+        // A connection back along an existing one is the same undirected edge.
+        graph.add_connection(&1, &0);
+        assert_eq!(graph.undirected_degree(&1), Some(4));
+        assert_eq!(graph.undirected_degree(&0), Some(1));
+        assert_eq!(graph.degree(&1), Some(5));
+
+        graph.add_connection(&1, &2);
+        graph.add_connection(&1, &2);
+        assert_eq!(graph.undirected_degree(&1), Some(5));
+    }
+
+    #[test]
+    fn test_diff() {
+        let build = || {
+            let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+                vec![
+                    String::from("node-1"),
+                    String::from("node-2"),
+                    String::from("node-3"),
+                ]
+            );
+            graph.add_connection(&0, &1);
+            graph.add_connection(&1, &2);
+            graph
+        };
+        let before = build();
+        let mut after = build();
+        after.add_connection(&2, &0);
+
+        let diff = before.diff(&after);
+        assert_eq!(diff.added_edges, vec![(2, 0)]);
+        assert!(diff.removed_edges.is_empty());
+        assert!(diff.added_nodes.is_empty());
+        assert!(diff.removed_nodes.is_empty());
+
+        let inserted = after.insert(String::from("node-4"));
+        after.add_connection(&1, &inserted);
+        after.add_connection(&0, &1);
+        after.remove_connection(&1, &2);
+        let diff = after.diff(&before);
+        assert_eq!(diff.removed_nodes, vec![3]);
+        assert!(diff.added_nodes.is_empty());
+        assert_eq!(diff.added_edges, vec![(1, 2)]);
+        assert_eq!(diff.removed_edges, vec![(0, 1), (1, 3), (2, 0)]);
+    }
+
+    #[test]
+    fn test_nodes_sorted_by() {
+        let graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("three"),
+                String::from("a"),
+                String::from("sixteen"),
+                String::from("be"),
+                String::from("one"),
+            ]
+        );
+
+        assert_eq!(graph.nodes_sorted_by(|value| value.len()), vec![1, 3, 4, 0, 2]);
+        assert_eq!(graph.nodes_sorted_by(|value| value.clone()), vec![1, 3, 4, 2, 0]);
+    }
+
+    #[test]
+    fn test_to_bool_matrix() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=5).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&3, &4);
+
+        let matrix = graph.to_bool_matrix();
+        assert_eq!(matrix.len(), 5);
+        assert!(matrix.iter().all(|row| row.len() == 5));
+        for (i, j) in [(0, 1), (0, 3), (1, 0), (3, 4), (4, 4), (2, 3)] {
+            assert_eq!(matrix[i as usize][j as usize], graph.connection_count(&i, &j) > 0);
+        }
+        assert_eq!(matrix.iter().flatten().filter(|cell| **cell).count(), 5);
+
+        graph.remove(&1);
+        assert_eq!(graph.to_bool_matrix()[0], vec![false, true, false, false]);
+    }
+
+    #[test]
+    fn test_has_edge_and_contains_node() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=3).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &1);
+
+        assert!(graph.has_edge(&0, &1));
+        assert!(graph.has_edge(&1, &1));
+        assert!(!graph.has_edge(&1, &0));
+        assert!(!graph.has_edge(&7, &1));
+        assert!(graph.contains_node(&2));
+        assert!(!graph.contains_node(&3));
+
+        graph.remove(&1);
+        assert!(!graph.contains_node(&1));
+        assert!(!graph.has_edge(&0, &1));
+    }
+
+    #[test]
+    fn test_to_gml() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("\"node-3\""),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &2);
+
+        let gml = graph.to_gml();
+        assert_eq!(gml.matches("node [").count(), 3);
+        assert_eq!(gml.matches("edge [").count(), 3);
+        assert!(gml.contains("node [ id 2 label \"&quot;node-3&quot;\" ]"));
+        assert!(gml.contains("edge [ source 0 target 1 ]"));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("say \"hi\""),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&0, &2);
+        graph.add_connection(&0, &1);
+        graph.add_connection(&2, &1);
+
+        assert_eq!(
+            graph.to_dot(),
+            "digraph {\n  0 [label=\"node-1\"];\n  1 [label=\"say \\\"hi\\\"\"];\n  \
+             2 [label=\"node-3\"];\n  0 -> 1;\n  0 -> 2;\n  2 -> 1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_edge_payloads() {
+        let mut graph: AdjacencyListGraph<u16, String, i32, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("alice"),
+                String::from("bob"),
+                String::from("carol"),
+            ]
+        );
+        graph.add_edge_with_payload(&0, &1, 5, String::from("manages"));
+        graph.add_weighted_connection(&0, &2, 3);
+
+        assert_eq!(graph.get_edge_payload(&0, &1), Some(&String::from("manages")));
+        assert_eq!(graph.get_edge_payload(&0, &2), Some(&String::new()));
+        assert_eq!(graph.get_edge_payload(&1, &0), None);
+        assert!(graph.get_weighted_edges(&0).unwrap().eq(vec![(&1, &5), (&2, &3)]));
+        assert!(!graph.add_edge_with_payload(&7, &0, 1, String::from("knows")));
+    }
+
+    #[test]
+    fn test_swap_nodes() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &0);
+        graph.add_connection(&2, &2);
+
+        assert!(graph.swap_nodes(&0, &2));
+        assert_eq!(graph.get_value(&0), Some(&String::from("node-3")));
+        assert_eq!(graph.get_value(&2), Some(&String::from("node-1")));
+        assert!(graph.get_edges(&0).unwrap().eq(vec![&2, &0]));
+        assert!(graph.get_edges(&1).unwrap().eq(vec![&0]));
+        assert!(graph.get_edges(&2).unwrap().eq(vec![&1]));
+
+        assert!(!graph.swap_nodes(&0, &5));
+        graph.remove(&1);
+        assert!(!graph.swap_nodes(&1, &2));
+    }
+
+    #[test]
+    fn test_transpose_in_place() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 4);
+        graph.add_weighted_connection(&1, &2, 7);
+
+        graph.transpose_in_place();
+        assert!(graph.get_weighted_edges(&0).unwrap().eq(vec![]));
+        assert!(graph.get_weighted_edges(&1).unwrap().eq(vec![(&0, &4)]));
+        assert!(graph.get_weighted_edges(&2).unwrap().eq(vec![(&1, &7)]));
+        assert_eq!(graph.get_value(&2), Some(&String::from("node-3")));
+    }
+
+    #[test]
+    fn test_from_nodes_and_edges() {
+        let (graph, skipped): (AdjacencyListGraph<u8, String, i32>, usize) =
+            AdjacencyListGraph::from_nodes_and_edges(
+                vec![
+                    String::from("node-1"),
+                    String::from("node-2"),
+                    String::from("node-3"),
+                ],
+                vec![(0, 1, 5), (1, 2, 3), (0, 2, 9), (2, 3, 1), (300, 0, 1)]
+            );
+
+        assert_eq!(skipped, 2);
+        assert!(graph.get_weighted_edges(&0).unwrap().eq(vec![(&1, &5), (&2, &9)]));
+        assert!(graph.get_weighted_edges(&1).unwrap().eq(vec![(&2, &3)]));
+        assert!(graph.get_weighted_edges(&2).unwrap().eq(vec![]));
+    }
+
+    #[test]
+    fn test_trim_to_reachable() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &1);
+
+        assert_eq!(graph.trim_to_reachable(&0), 2);
+        assert!(graph.node_keys().eq(vec![0, 1, 2]));
+        assert_eq!(graph.get_value(&2), Some(&String::from("node-3")));
+        assert!(graph.get_edges(&1).unwrap().eq(vec![&2]));
+        assert_eq!(graph.trim_to_reachable(&0), 0);
+        assert_eq!(graph.trim_to_reachable(&4), 0);
+    }
+
+    #[test]
+    fn test_edge_position() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&0, &3);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &3);
+
+        assert_eq!(graph.edge_position(&0, &2), Some(1));
+        assert_eq!(graph.edge_position(&2, &3), Some(0));
+        assert_eq!(graph.edge_position(&3, &0), None);
+        assert_eq!(graph.edge_position(&9, &0), None);
+    }
+
+    #[test]
+    fn test_add_connection_acyclic() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+
+        assert!(graph.add_connection_acyclic(&0, &1));
+        assert!(graph.add_connection_acyclic(&1, &2));
+        assert!(graph.add_connection_acyclic(&0, &2));
+        assert!(!graph.add_connection_acyclic(&2, &0));
+        assert!(!graph.add_connection_acyclic(&2, &2));
+        assert!(graph.get_edges(&2).unwrap().eq(vec![] as Vec<&u16>));
+        assert!(!graph.has_cycle());
+    }
+
+    #[test]
+    fn test_all_pairs_shortest_paths() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 4);
+        graph.add_weighted_connection(&0, &2, 1);
+        graph.add_weighted_connection(&2, &1, 2);
+        graph.add_weighted_connection(&1, &3, 1);
+
+        let (distances, next) = graph.all_pairs_shortest_paths_with_next();
+        assert_eq!(distances[0], vec![Some(0), Some(3), Some(1), Some(4)]);
+        assert_eq!(distances[3], vec![None, None, None, Some(0)]);
+
+        let mut path = vec![0];
+        while let Some(hop) = next[*path.last().unwrap() as usize][3].filter(|hop| *hop != 3) {
+            path.push(hop);
+        }
+        path.push(3);
+        assert_eq!(path, vec![0, 2, 1, 3]);
+
+        graph.remove(&2);
+        let distances = graph.all_pairs_shortest_paths();
+        assert_eq!(distances[0], vec![Some(0), Some(4), None, Some(5)]);
+        assert_eq!(distances[2], vec![None, None, None, None]);
+
+        assert!(graph.try_all_pairs_shortest_paths().is_ok());
+        graph.add_weighted_connection(&3, &0, -6);
+        assert_eq!(graph.try_all_pairs_shortest_paths(), Err(GraphError::NegativeCycle));
+    }
+
+    #[test]
+    fn test_path_and_cycle() {
+        let nodes: Vec<String> = (1..=4).map(|index| format!("node-{}", index)).collect();
+        let path: AdjacencyListGraph<u16, String> = AdjacencyListGraph::path(nodes.clone());
+        let cycle: AdjacencyListGraph<u16, String> = AdjacencyListGraph::cycle(nodes);
+
+        assert_eq!(path.to_adjacency_vec(), vec![vec![1], vec![2], vec![3], vec![]]);
+        assert_eq!(path.to_adjacency_vec().concat().len(), 3);
+        assert_eq!(cycle.to_adjacency_vec().concat().len(), 4);
+        assert_eq!(cycle.connection_count(&3, &0), 1);
+        assert!(!path.has_cycle());
+        assert!(cycle.has_cycle());
+
+        let empty: AdjacencyListGraph<u16, String> = AdjacencyListGraph::cycle(vec![]);
+        assert!(empty.to_adjacency_vec().is_empty());
+    }
+
+    #[test]
+    fn test_deduplicate_by_value() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("a"),
+                String::from("b"),
+                String::from("a"),
+                String::from("c"),
+                String::from("d"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&2, &1);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&4, &2);
+        graph.add_connection(&4, &0);
+
+        assert_eq!(graph.deduplicate_by_value(), 1);
+        assert!(graph.node_keys().eq(vec![0, 1, 3, 4]));
+        assert!(graph.get_edges(&0).unwrap().eq(vec![&1, &3]));
+        assert!(graph.get_edges(&4).unwrap().eq(vec![&0]));
+        assert_eq!(graph.deduplicate_by_value(), 0);
+    }
+
+    #[test]
+    fn test_deduplicate_by_value_reuses_keys_in_order() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("a"),
+                String::from("b"),
+                String::from("a"),
+                String::from("c"),
+                String::from("a"),
+            ]
+        );
+
+        assert_eq!(graph.deduplicate_by_value(), 2);
+        assert_eq!(graph.insert(String::from("d")), 4);
+        assert_eq!(graph.insert(String::from("e")), 2);
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let graph: AdjacencyListGraph<u16, String> = vec![(0, 1), (1, 3), (3, 0)]
+            .into_iter()
+            .collect();
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.get_value(&2), Some(&String::new()));
+        assert!(graph.get_edges(&1).unwrap().eq(vec![&3]));
+        assert_eq!(graph.get_edges(&2).unwrap().count(), 0);
+
+        let weighted: AdjacencyListGraph<u8, u32, i32> = [(2, 0, 5), (0, 2, -1), (0, 1, 3)]
+            .into_iter()
+            .collect();
+        assert_eq!(weighted.node_count(), 3);
+        assert!(weighted.get_weighted_edges(&0).unwrap().eq(vec![(&2, &-1), (&1, &3)]));
+        assert!(weighted.get_weighted_edges(&2).unwrap().eq(vec![(&0, &5)]));
+
+        let empty: AdjacencyListGraph<u16, String> = std::iter::empty::<(u16, u16)>().collect();
+        assert_eq!(empty.node_count(), 0);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            (1..=4).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_weighted_connection(&0, &1, 4);
+        graph.add_weighted_connection(&1, &2, 2);
+        graph.add_weighted_connection(&3, &1, 7);
+        graph.add_weighted_connection(&2, &2, 1);
+        graph.remove(&3);
+
+        let transposed = graph.transpose();
+        assert!(transposed.get_weighted_edges(&1).unwrap().eq(vec![(&0, &4)]));
+        assert!(transposed.get_weighted_edges(&2).unwrap().eq(vec![(&1, &2), (&2, &1)]));
+        assert_eq!(transposed.get_value(&3), None);
+        assert_eq!(transposed.get_value(&0), Some(&String::from("node-1")));
+
+        let mut original: Vec<(u16, u16, i32)> = graph.all_weighted_edges()
+            .map(|(source, destination, weight)| (source, *destination, *weight))
+            .collect();
+        let mut round_trip: Vec<(u16, u16, i32)> = transposed.transpose()
+            .all_weighted_edges()
+            .map(|(source, destination, weight)| (source, *destination, *weight))
+            .collect();
+        original.sort();
+        round_trip.sort();
+        assert_eq!(original, round_trip);
+    }
+
+    #[test]
+    fn test_shortest_path_dag() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            (1..=5).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_weighted_connection(&0, &1, 2);
+        graph.add_weighted_connection(&1, &3, 3);
+        graph.add_weighted_connection(&0, &2, 1);
+        graph.add_weighted_connection(&2, &3, 4);
+        graph.add_weighted_connection(&0, &3, 6);
+        graph.add_weighted_connection(&3, &4, 1);
+        graph.add_weighted_connection(&4, &0, 1);
+
+        let dag = graph.shortest_path_dag(&0);
+        assert!(dag.get_weighted_edges(&0).unwrap().eq(vec![(&1, &2), (&2, &1)]));
+        assert!(dag.get_weighted_edges(&1).unwrap().eq(vec![(&3, &3)]));
+        assert!(dag.get_weighted_edges(&2).unwrap().eq(vec![(&3, &4)]));
+        assert!(dag.get_weighted_edges(&3).unwrap().eq(vec![(&4, &1)]));
+        assert!(dag.get_weighted_edges(&4).unwrap().eq(vec![]));
+        assert_eq!(dag.node_count(), 5);
+        assert_eq!(dag.get_value(&4), Some(&String::from("node-5")));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            (1..=4).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_weighted_connection(&0, &1, 4);
+        graph.add_weighted_connection(&1, &2, -2);
+        graph.add_weighted_connection(&2, &0, 7);
+        graph.remove(&3);
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let mut restored: AdjacencyListGraph<u16, String, i32> =
+            serde_json::from_str(&json).unwrap();
+        assert!(restored.node_keys().eq(graph.node_keys()));
+        assert!(restored.all_weighted_edges().eq(graph.all_weighted_edges()));
+        assert_eq!(restored.get_value(&1), Some(&String::from("node-2")));
+        assert_eq!(restored.insert(String::from("node-5")), 3);
+
+        let unweighted: AdjacencyListGraph<u16, String> = AdjacencyListGraph::path(
+            vec![String::from("node-1"), String::from("node-2")]
+        );
+        let json = serde_json::to_string(&unweighted).unwrap();
+        let restored: AdjacencyListGraph<u16, String> = serde_json::from_str(&json).unwrap();
+        assert!(restored.get_edges(&0).unwrap().eq(vec![&1]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_malformed_graphs() {
+        type Restored = Result<AdjacencyListGraph<u16, String, i32>, serde_json::Error>;
+        let edge = |destination: u16| serde_json::json!({
+            "destination": destination,
+            "weight": 1,
+            "payload": null
+        });
+
+        // A connection to a node past the end of the graph.
+        let payload = serde_json::json!({
+            "nodes": ["node-1", "node-2"],
+            "edges": [[edge(1)], [edge(5)]]
+        });
+        let restored: Restored = serde_json::from_value(payload);
+        assert!(restored.is_err());
+
+        // A connection to a removed node.
+        let payload = serde_json::json!({
+            "nodes": ["node-1", null],
+            "edges": [[edge(1)], []]
+        });
+        let restored: Restored = serde_json::from_value(payload);
+        assert!(restored.is_err());
+
+        // Fewer rows of connections than nodes.
+        let payload = serde_json::json!({
+            "nodes": ["node-1", "node-2"],
+            "edges": [[]]
+        });
+        let restored: Restored = serde_json::from_value(payload);
+        assert!(restored.is_err());
+
+        // The free slots are rebuilt from the tombstones rather than trusted.
+        let payload = serde_json::json!({
+            "nodes": ["node-1", null, "node-3"],
+            "edges": [[edge(2)], [], []],
+            "free": [0, 7]
+        });
+        let mut restored: AdjacencyListGraph<u16, String, i32> =
+            serde_json::from_value(payload).unwrap();
+        assert_eq!(restored.insert(String::from("node-4")), 1);
+        assert_eq!(restored.insert(String::from("node-5")), 3);
+        assert_eq!(restored.get_value(&0), Some(&String::from("node-1")));
+    }
+
     #[test]
     fn test_adjacency_list_memory_layout() {
         // The memory size of unweighted edge structs is just the key size.
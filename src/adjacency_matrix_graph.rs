@@ -0,0 +1,433 @@
+use num::traits::{PrimInt, Zero};
+
+use crate::error::GraphError;
+use crate::graph::Graph;
+use crate::weighted_graph::{NoWeight, WeightedGraph};
+
+/// A graph backed by a dense adjacency matrix, which makes checking for and looking up a
+/// connection between two nodes O(1) at the cost of O(n²) memory. The matrix holds at most one
+/// connection from one node to another, so adding a connection that already exists replaces its
+/// weight. The matrix doubles its side whenever an inserted node doesn't fit, so inserting a node
+/// takes amortized O(n) time.
+#[derive(Debug)]
+pub struct AdjacencyMatrixGraph<K, V, W = NoWeight>
+where
+    K: PrimInt + Copy,
+    V: PartialEq,
+    W: PartialOrd + Zero + Copy
+{
+    // Removed nodes leave a `None` tombstone behind so that the keys of other nodes stay valid.
+    nodes: Vec<Option<V>>,
+    // The key of every slot, so that edge iterators can hand out references to keys.
+    keys: Vec<K>,
+    // Row-major `stride * stride` matrix, where the cell at `source * stride + destination` holds
+    // the weight of the connection between them, if there is one. Cells past the last slot are
+    // always empty.
+    matrix: Vec<Option<W>>,
+    // The side of the matrix, which is at least the number of slots.
+    stride: usize,
+    // Indices of tombstoned slots, reused by `insert` before the graph grows.
+    free: Vec<usize>
+}
+
+impl<K, V, W> AdjacencyMatrixGraph<K, V, W>
+where
+    K: PrimInt + Copy,
+    V: PartialEq,
+    W: PartialOrd + Zero + Copy
+{
+    /// Creates a graph from its node values. Panics if the key type can't represent the key of
+    /// every node; use `try_new` to handle that case.
+    /// # Arguments
+    /// * `nodes` - the values of the nodes in the graph.
+    pub fn new(nodes: Vec<V>) -> AdjacencyMatrixGraph<K, V, W> {
+        AdjacencyMatrixGraph::try_new(nodes).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Creates a graph from its node values, or returns `GraphError::CapacityExceeded` if the key
+    /// type can't represent the key of every node.
+    /// # Arguments
+    /// * `nodes` - the values of the nodes in the graph.
+    pub fn try_new(nodes: Vec<V>) -> Result<AdjacencyMatrixGraph<K, V, W>, GraphError> {
+        let size = nodes.len();
+        let keys: Vec<K> = (0..size)
+            .map(K::from)
+            .collect::<Option<Vec<K>>>()
+            .ok_or(GraphError::CapacityExceeded)?;
+        Ok(AdjacencyMatrixGraph {
+            nodes: nodes.into_iter().map(Some).collect(),
+            keys,
+            matrix: vec![None; size * size],
+            stride: size,
+            free: vec![]
+        })
+    }
+
+    /// Returns the index of a node's slot if the key refers to a node in the graph.
+    fn index(&self, key: &K) -> Option<usize> {
+        let index = key.to_usize()?;
+        self.nodes.get(index)?.as_ref()?;
+        Some(index)
+    }
+
+    /// Returns the matrix cell for the connection between two nodes in the graph.
+    fn cell(&self, source: &K, destination: &K) -> Option<usize> {
+        Some(self.index(source)? * self.stride + self.index(destination)?)
+    }
+
+    /// Returns the cells of a slot's row that belong to existing slots.
+    fn row(&self, index: usize) -> &[Option<W>] {
+        let start = index * self.stride;
+        &self.matrix[start..start + self.nodes.len()]
+    }
+
+    /// Doubles the side of the matrix, copying the existing rows over.
+    fn grow(&mut self) {
+        let stride = (self.stride * 2).max(1);
+        let mut matrix = vec![None; stride * stride];
+        for row in 0..self.nodes.len() {
+            matrix[row * stride..row * stride + self.nodes.len()].copy_from_slice(self.row(row));
+        }
+        self.matrix = matrix;
+        self.stride = stride;
+    }
+}
+
+pub struct EdgeDestinationIterator<'a, K, W> {
+    iter: std::iter::Enumerate<std::slice::Iter<'a, Option<W>>>,
+    keys: &'a [K]
+}
+
+impl<'a, K, W> Iterator for EdgeDestinationIterator<'a, K, W> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let keys = self.keys;
+        self.iter.find(|(_, cell)| cell.is_some()).map(|(index, _)| &keys[index])
+    }
+}
+
+impl<'a, K, W> DoubleEndedIterator for EdgeDestinationIterator<'a, K, W> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let keys = self.keys;
+        self.iter.rfind(|(_, cell)| cell.is_some()).map(|(index, _)| &keys[index])
+    }
+}
+
+pub struct WeightedEdgeIterator<'a, K, W> {
+    iter: std::iter::Enumerate<std::slice::Iter<'a, Option<W>>>,
+    keys: &'a [K]
+}
+
+impl<'a, K, W> Iterator for WeightedEdgeIterator<'a, K, W> {
+    type Item = (&'a K, &'a W);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let keys = self.keys;
+        self.iter.find_map(|(index, cell)| cell.as_ref().map(|weight| (&keys[index], weight)))
+    }
+}
+
+impl<'a, K, W> DoubleEndedIterator for WeightedEdgeIterator<'a, K, W> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let keys = self.keys;
+        self.iter
+            .by_ref()
+            .rev()
+            .find_map(|(index, cell)| cell.as_ref().map(|weight| (&keys[index], weight)))
+    }
+}
+
+pub struct NodeKeyIterator<'a, K, V> {
+    iter: std::iter::Enumerate<std::slice::Iter<'a, Option<V>>>,
+    _key: std::marker::PhantomData<K>,
+}
+
+impl<'a, K, V> Iterator for NodeKeyIterator<'a, K, V>
+where
+    K: PrimInt
+{
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .find(|(_, node)| node.is_some())
+            .map(|(index, _)| K::from(index).unwrap())
+    }
+}
+
+impl<'a, K, V, W> Graph<'a, K, V> for AdjacencyMatrixGraph<K, V, W>
+where
+    K: PrimInt + Copy + 'a,
+    V: PartialEq + 'a,
+    W: PartialOrd + Zero + Copy + 'a
+{
+    type EdgeIterator = EdgeDestinationIterator<'a, K, W>;
+    type NodeKeyIterator = NodeKeyIterator<'a, K, V>;
+
+    fn try_insert(&mut self, value: V) -> Result<K, GraphError> {
+        if let Some(index) = self.free.pop() {
+            self.nodes[index] = Some(value);
+            return Ok(self.keys[index]);
+        }
+
+        let key = K::from(self.nodes.len()).ok_or(GraphError::CapacityExceeded)?;
+        if self.nodes.len() == self.stride {
+            self.grow();
+        }
+        self.nodes.push(Some(value));
+        self.keys.push(key);
+        Ok(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.index(key)?;
+        let stride = self.stride;
+        for other in 0..self.nodes.len() {
+            self.matrix[index * stride + other] = None;
+            self.matrix[other * stride + index] = None;
+        }
+        self.free.push(index);
+        self.nodes[index].take()
+    }
+
+    fn add_connection(&mut self, source: &K, destination: &K) -> bool {
+        self.add_weighted_connection(source, destination, W::zero())
+    }
+
+    fn remove_connection(&mut self, source: &K, destination: &K) -> bool {
+        self.cell(source, destination)
+            .is_some_and(|cell| self.matrix[cell].take().is_some())
+    }
+
+    fn remove_incoming(&mut self, key: &K) -> usize {
+        let Some(index) = self.index(key) else {
+            return 0;
+        };
+        let stride = self.stride;
+        (0..self.nodes.len())
+            .filter_map(|other| self.matrix[other * stride + index].take())
+            .count()
+    }
+
+    fn remove_outgoing(&mut self, key: &K) -> usize {
+        let Some(index) = self.index(key) else {
+            return 0;
+        };
+        let start = index * self.stride;
+        self.matrix[start..start + self.nodes.len()]
+            .iter_mut()
+            .filter_map(Option::take)
+            .count()
+    }
+
+    fn get(&'a self, key: &K) -> Option<(&'a V, Self::EdgeIterator)> {
+        Some((self.get_value(key)?, self.get_edges(key)?))
+    }
+
+    fn get_value(&self, key: &K) -> Option<&V> {
+        self.nodes.get(key.to_usize()?)?.as_ref()
+    }
+
+    fn get_edges(&'a self, key: &K) -> Option<Self::EdgeIterator> {
+        let index = self.index(key)?;
+        Some(EdgeDestinationIterator {
+            iter: self.row(index).iter().enumerate(),
+            keys: &self.keys
+        })
+    }
+
+    fn has_edge(&'a self, source: &K, destination: &K) -> bool {
+        self.cell(source, destination).is_some_and(|cell| self.matrix[cell].is_some())
+    }
+
+    fn node_count(&'a self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    fn edge_count(&'a self) -> usize {
+        self.matrix.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    fn node_keys(&'a self) -> Self::NodeKeyIterator {
+        NodeKeyIterator {
+            iter: self.nodes.iter().enumerate(),
+            _key: std::marker::PhantomData
+        }
+    }
+}
+
+impl<'a, K, V, W> WeightedGraph<'a, K, V, W> for AdjacencyMatrixGraph<K, V, W>
+where
+    K: PrimInt + Copy + 'a,
+    V: PartialEq + 'a,
+    W: PartialOrd + Zero + Copy + 'a
+{
+    type WeightedEdgeIterator = WeightedEdgeIterator<'a, K, W>;
+
+    fn add_weighted_connection(&mut self, source: &K, destination: &K, weight: W) -> bool {
+        let Some(cell) = self.cell(source, destination) else {
+            return false;
+        };
+        self.matrix[cell] = Some(weight);
+        true
+    }
+
+    fn remove_weighted_connection(&mut self, source: &K, destination: &K, weight: &W) -> bool
+    where
+        W: PartialEq
+    {
+        let Some(cell) = self.cell(source, destination) else {
+            return false;
+        };
+        if self.matrix[cell].as_ref() == Some(weight) {
+            self.matrix[cell] = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn get_weight(&'a self, source: &K, destination: &K) -> Option<&'a W> {
+        self.matrix[self.cell(source, destination)?].as_ref()
+    }
+
+    fn get_weighted(&'a self, key: &K) -> Option<(&'a V, Self::WeightedEdgeIterator)> {
+        Some((self.get_value(key)?, self.get_weighted_edges(key)?))
+    }
+
+    fn get_weighted_edges(&'a self, key: &K) -> Option<Self::WeightedEdgeIterator> {
+        let index = self.index(key)?;
+        Some(WeightedEdgeIterator {
+            iter: self.row(index).iter().enumerate(),
+            keys: &self.keys
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::searchable_graph::SearchableGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_getters() {
+        let mut graph: AdjacencyMatrixGraph<u16, String> = AdjacencyMatrixGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &3);
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&0, &1);
+        assert!(!graph.add_connection(&0, &9));
+
+        assert!(graph.get_edges(&0).unwrap().eq(vec![&1, &3]));
+        assert!(graph.has_edge(&0, &3));
+        assert!(!graph.has_edge(&3, &0));
+        assert!(!graph.has_edge(&0, &9));
+        assert!(graph.contains_node(&3));
+        assert!(!graph.contains_node(&4));
+        assert!(graph.get_edges(&0).unwrap().rev().eq(vec![&3, &1]));
+        let (node_3, edges_3) = graph.get(&3).unwrap();
+        assert_eq!(node_3, &String::from("node-4"));
+        assert_eq!(edges_3.count(), 0);
+        assert_eq!(graph.find_path_bfs(&0, &2), Some(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn test_weighted_getters() {
+        let mut graph: AdjacencyMatrixGraph<u16, String, i32> = AdjacencyMatrixGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 4);
+        graph.add_weighted_connection(&0, &2, 1);
+        graph.add_weighted_connection(&2, &1, 2);
+        graph.add_weighted_connection(&0, &1, 5);
+
+        assert!(graph.get_weighted_edges(&0).unwrap().eq(vec![(&1, &5), (&2, &1)]));
+        assert_eq!(graph.get_weight(&2, &1), Some(&2));
+        assert_eq!(graph.get_weight(&1, &2), None);
+        assert_eq!(graph.reachable_sorted_by_cost(&0), vec![(0, 0), (2, 1), (1, 3)]);
+        assert!(!graph.remove_weighted_connection(&0, &1, &4));
+        assert!(graph.remove_weighted_connection(&0, &1, &5));
+        assert!(graph.get_weighted_edges(&0).unwrap().eq(vec![(&2, &1)]));
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut graph: AdjacencyMatrixGraph<u16, String> = AdjacencyMatrixGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &0);
+
+        let inserted = graph.insert(String::from("node-3"));
+        assert_eq!(inserted, 2);
+        graph.add_connection(&2, &0);
+        assert!(graph.get_edges(&0).unwrap().eq(vec![&1]));
+        assert!(graph.get_edges(&1).unwrap().eq(vec![&0]));
+
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.remove(&0), Some(String::from("node-1")));
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.node_count(), 2);
+        assert!(graph.get_edges(&0).is_none());
+        assert_eq!(graph.get_edges(&1).unwrap().count(), 0);
+        assert_eq!(graph.node_keys().collect::<Vec<_>>(), vec![1, 2]);
+
+        assert_eq!(graph.insert(String::from("node-4")), 0);
+        assert_eq!(graph.get_edges(&2).unwrap().count(), 0);
+        graph.add_connection(&1, &0);
+        graph.add_connection(&2, &0);
+        assert_eq!(graph.remove_incoming(&0), 2);
+        assert!(!graph.remove_connection(&1, &2));
+        graph.add_connection(&1, &2);
+        assert_eq!(graph.remove_outgoing(&1), 1);
+    }
+
+    #[test]
+    fn test_try_insert_capacity() {
+        let mut graph: AdjacencyMatrixGraph<u8, usize> =
+            AdjacencyMatrixGraph::new((0..256).collect());
+        assert_eq!(graph.try_insert(256), Err(GraphError::CapacityExceeded));
+        assert_eq!(graph.node_count(), 256);
+
+        graph.remove(&3);
+        assert_eq!(graph.try_insert(256), Ok(3));
+
+        let too_many = AdjacencyMatrixGraph::<u8, usize>::try_new((0..257).collect());
+        assert!(matches!(too_many, Err(GraphError::CapacityExceeded)));
+    }
+
+    #[test]
+    fn test_insert_grows_matrix() {
+        let mut graph: AdjacencyMatrixGraph<u16, usize, i32> = AdjacencyMatrixGraph::new(vec![]);
+        for value in 0..100 {
+            let key = graph.insert(value);
+            if key > 0 {
+                graph.add_weighted_connection(&(key - 1), &key, value as i32);
+                graph.add_weighted_connection(&key, &0, 1);
+            }
+        }
+
+        assert_eq!(graph.node_count(), 100);
+        assert_eq!(graph.edge_count(), 198);
+        assert!(graph.get_weighted_edges(&41).unwrap().eq(vec![(&0, &1), (&42, &42)]));
+        assert_eq!(graph.get_weight(&98, &99), Some(&99));
+        assert_eq!(graph.remove_incoming(&0), 99);
+        assert_eq!(graph.get_edges(&99).unwrap().count(), 0);
+    }
+}
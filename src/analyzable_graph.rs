@@ -0,0 +1,662 @@
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use crate::graph::Graph;
+use crate::weighted_graph::MinScored;
+
+/// Builds an undirected view of the graph, mapping every node to the nodes it is connected to
+/// by an edge in either direction.
+pub(crate) fn undirected_adjacency<'a, G, K, V>(graph: &'a G) -> HashMap<K, Vec<K>>
+where
+    G: Graph<'a, K, V> + ?Sized,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+    let mut adjacency: HashMap<K, Vec<K>> = graph.node_keys().map(|key| (key, vec![])).collect();
+    for source in graph.node_keys() {
+        if let Some(edges) = graph.get_edges(&source) {
+            for destination in edges {
+                adjacency.entry(source).or_default().push(*destination);
+                adjacency.entry(*destination).or_default().push(source);
+            }
+        }
+    }
+    adjacency
+}
+
+/// Builds the simple undirected view of the graph, without self-loops or parallel edges.
+pub(crate) fn simple_adjacency<'a, G, K, V>(graph: &'a G) -> HashMap<K, Vec<K>>
+where
+    G: Graph<'a, K, V> + ?Sized,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+    let mut adjacency = undirected_adjacency(graph);
+    for (node, neighbors) in adjacency.iter_mut() {
+        let mut seen: HashSet<K> = HashSet::from([*node]);
+        neighbors.retain(|neighbor| seen.insert(*neighbor));
+    }
+    adjacency
+}
+
+/// Returns the determinant of a square integer matrix using fraction-free Bareiss elimination,
+/// or `None` if an intermediate value overflows.
+fn integer_determinant(mut matrix: Vec<Vec<i128>>) -> Option<i128> {
+    let size = matrix.len();
+    let mut sign = 1;
+    let mut previous_pivot = 1;
+
+    for k in 0..size {
+        if matrix[k][k] == 0 {
+            let Some(swap) = (k + 1..size).find(|&row| matrix[row][k] != 0) else {
+                return Some(0);
+            };
+            matrix.swap(k, swap);
+            sign = -sign;
+        }
+        for i in k + 1..size {
+            for j in k + 1..size {
+                let product = matrix[i][j].checked_mul(matrix[k][k])?;
+                let cross = matrix[i][k].checked_mul(matrix[k][j])?;
+                matrix[i][j] = product.checked_sub(cross)? / previous_pivot;
+            }
+        }
+        previous_pivot = matrix[k][k];
+    }
+
+    Some(if size == 0 { 1 } else { sign * matrix[size - 1][size - 1] })
+}
+
+/// Tries to match a left node by finding an augmenting path from it through the right nodes
+/// that haven't been visited yet, updating the matching along the path if one is found. The path
+/// is searched with an explicit stack, so long alternating paths can't overflow the call stack.
+fn augment<K>(
+    adjacency: &HashMap<K, Vec<K>>,
+    node: K,
+    matched: &mut HashMap<K, K>,
+    visited: &mut HashSet<K>
+) -> bool
+where
+    K: Copy + Hash + Eq
+{
+    // Each frame holds a left node and the index of its next candidate to try, and `path` holds
+    // the right node each frame went through to reach the frame above it.
+    let mut stack: Vec<(K, usize)> = vec![(node, 0)];
+    let mut path: Vec<K> = vec![];
+
+    while let Some((left, next)) = stack.last_mut() {
+        let candidates = &adjacency[left];
+        let mut untried = candidates[*next..].iter();
+        let Some(offset) = untried.position(|candidate| visited.insert(*candidate)) else {
+            stack.pop();
+            path.pop();
+            continue;
+        };
+        let candidate = candidates[*next + offset];
+        *next += offset + 1;
+
+        match matched.get(&candidate) {
+            Some(&other) => {
+                path.push(candidate);
+                stack.push((other, 0));
+            }
+            None => {
+                matched.insert(candidate, *left);
+                for (right, (left, _)) in path.into_iter().zip(stack) {
+                    matched.insert(right, left);
+                }
+                return true;
+            }
+        }
+    }
+    false
+}
+
+pub trait AnalyzableGraph<'a, K, V>: Graph<'a, K, V>
+where
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+    /// Returns whether the nodes of the graph can be split into two sets such that every edge
+    /// connects a node in one set to a node in the other. Edge directions and weights are ignored.
+    fn is_bipartite(&'a self) -> bool {
+        let adjacency = undirected_adjacency(self);
+        let mut colors: HashMap<K, bool> = HashMap::new();
+
+        for start in self.node_keys() {
+            if colors.contains_key(&start) {
+                continue;
+            }
+            colors.insert(start, false);
+            let mut queue: VecDeque<K> = VecDeque::new();
+            queue.push_back(start);
+
+            while let Some(node) = queue.pop_front() {
+                let color = colors[&node];
+                for neighbor in adjacency.get(&node).into_iter().flatten() {
+                    match colors.get(neighbor) {
+                        Some(neighbor_color) if *neighbor_color == color => return false,
+                        Some(_) => {}
+                        None => {
+                            colors.insert(*neighbor, !color);
+                            queue.push_back(*neighbor);
+                        }
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns whether the graph contains a cycle of odd length when edge directions are ignored,
+    /// which is the case exactly when the graph isn't bipartite.
+    fn has_odd_cycle(&'a self) -> bool {
+        !self.is_bipartite()
+    }
+
+    /// Returns the shared out-degree of the nodes if every node in the graph has the same
+    /// number of outgoing connections, otherwise `None`. An empty graph is regular with degree 0.
+    fn is_regular(&'a self) -> Option<usize> {
+        let mut degrees = self.node_keys()
+            .map(|key| self.get_edges(&key).map_or(0, |edges| edges.count()));
+        let Some(degree) = degrees.next() else {
+            return Some(0);
+        };
+        degrees.all(|other| other == degree).then_some(degree)
+    }
+
+    /// Returns the degree assortativity coefficient of the graph, i.e. the Pearson correlation
+    /// between the degrees of the nodes at either end of each edge, treating every pair of
+    /// connected nodes as joined by a single undirected edge and ignoring self-loops, so a mutual
+    /// `a -> b` and `b -> a` pair is one edge. Returns `None` if the graph has no edges, or if
+    /// every edge connects nodes of equal degree, in which case the correlation is undefined.
+    fn degree_assortativity(&'a self) -> Option<f64> {
+        let adjacency = simple_adjacency(self);
+        let (mut count, mut sum, mut sum_squares, mut sum_products) = (0.0, 0.0, 0.0, 0.0);
+
+        // Every edge is listed from both ends, so each is counted in both directions.
+        for source in self.node_keys() {
+            let x = adjacency[&source].len() as f64;
+            for destination in &adjacency[&source] {
+                let y = adjacency[destination].len() as f64;
+                count += 1.0;
+                sum += x;
+                sum_squares += x * x;
+                sum_products += x * y;
+            }
+        }
+
+        if count == 0.0 {
+            return None;
+        }
+        let mean = sum / count;
+        let variance = sum_squares / count - mean * mean;
+        if variance == 0.0 {
+            return None;
+        }
+        Some((sum_products / count - mean * mean) / variance)
+    }
+
+    /// Returns the number of spanning trees of the graph using Kirchhoff's matrix-tree theorem,
+    /// treating every pair of connected nodes as joined by a single undirected edge and ignoring
+    /// self-loops. Returns `None` for an empty graph, or if the count is too large to compute
+    /// exactly, which limits this to small graphs.
+    fn spanning_tree_count(&'a self) -> Option<u64> {
+        let keys: Vec<K> = self.node_keys().collect();
+        if keys.is_empty() {
+            return None;
+        }
+        let indices: HashMap<K, usize> = keys.iter()
+            .enumerate()
+            .map(|(index, key)| (*key, index))
+            .collect();
+        let mut connected: HashSet<(usize, usize)> = HashSet::new();
+        for (source, key) in keys.iter().enumerate() {
+            for edge in self.get_edges(key).into_iter().flatten() {
+                if let Some(&destination) = indices.get(edge) {
+                    if source != destination {
+                        connected.insert((source.min(destination), source.max(destination)));
+                    }
+                }
+            }
+        }
+
+        let mut laplacian = vec![vec![0i128; keys.len()]; keys.len()];
+        for (a, b) in connected {
+            laplacian[a][a] += 1;
+            laplacian[b][b] += 1;
+            laplacian[a][b] -= 1;
+            laplacian[b][a] -= 1;
+        }
+        let minor: Vec<Vec<i128>> = laplacian.into_iter()
+            .skip(1)
+            .map(|row| row.into_iter().skip(1).collect())
+            .collect();
+        integer_determinant(minor).and_then(|count| u64::try_from(count).ok())
+    }
+
+    /// Returns the edge betweenness of every connection in the graph, i.e. the number of shortest
+    /// paths between pairs of nodes that pass through it, with paths split evenly when a pair has
+    /// several shortest paths. This uses the edge variant of Brandes' algorithm, treating the
+    /// graph as unweighted, and parallel connections share a single entry.
+    fn edge_betweenness(&'a self) -> HashMap<(K, K), f64> {
+        let mut betweenness: HashMap<(K, K), f64> = HashMap::new();
+        let neighbors: HashMap<K, Vec<K>> = self.node_keys()
+            .map(|key| {
+                let mut seen: HashSet<K> = HashSet::new();
+                let edges: Vec<K> = self.get_edges(&key)
+                    .into_iter()
+                    .flatten()
+                    .copied()
+                    .filter(|edge| seen.insert(*edge))
+                    .collect();
+                for edge in &edges {
+                    betweenness.insert((key, *edge), 0.0);
+                }
+                (key, edges)
+            })
+            .collect();
+
+        for source in self.node_keys() {
+            let mut order: Vec<K> = vec![];
+            let mut predecessors: HashMap<K, Vec<K>> = HashMap::new();
+            let mut path_counts: HashMap<K, f64> = HashMap::from([(source, 1.0)]);
+            let mut distances: HashMap<K, usize> = HashMap::from([(source, 0)]);
+            let mut queue: VecDeque<K> = VecDeque::from([source]);
+
+            while let Some(node) = queue.pop_front() {
+                order.push(node);
+                let distance = distances[&node];
+                for edge in neighbors.get(&node).into_iter().flatten() {
+                    if !distances.contains_key(edge) {
+                        distances.insert(*edge, distance + 1);
+                        queue.push_back(*edge);
+                    }
+                    if distances[edge] == distance + 1 {
+                        *path_counts.entry(*edge).or_insert(0.0) += path_counts[&node];
+                        predecessors.entry(*edge).or_default().push(node);
+                    }
+                }
+            }
+
+            let mut dependencies: HashMap<K, f64> = HashMap::new();
+            while let Some(node) = order.pop() {
+                let dependency = dependencies.get(&node).copied().unwrap_or(0.0);
+                for predecessor in predecessors.get(&node).into_iter().flatten() {
+                    let share = path_counts[predecessor] / path_counts[&node] * (1.0 + dependency);
+                    *betweenness.entry((*predecessor, node)).or_insert(0.0) += share;
+                    *dependencies.entry(*predecessor).or_insert(0.0) += share;
+                }
+            }
+        }
+
+        betweenness
+    }
+
+    /// Returns the core number of every node in the graph, i.e. the largest `k` such that the
+    /// node belongs to a subgraph where every node has at least `k` neighbors, treating every
+    /// edge as undirected and ignoring self-loops and parallel edges. The nodes are peeled off in
+    /// order of their remaining degree, and each node's core number is the highest remaining
+    /// degree seen so far when it is removed.
+    fn core_numbers(&'a self) -> HashMap<K, usize> {
+        let adjacency = simple_adjacency(self);
+        let mut degrees: HashMap<K, usize> = self.node_keys().map(|key| (key, 0)).collect();
+        for (node, degree) in degrees.iter_mut() {
+            *degree = adjacency[node]
+                .iter()
+                .filter(|neighbor| self.get_value(neighbor).is_some())
+                .count();
+        }
+        let mut frontier: BinaryHeap<MinScored<usize, K>> = degrees.iter()
+            .map(|(key, degree)| MinScored { cost: *degree, item: *key })
+            .collect();
+
+        let mut cores: HashMap<K, usize> = HashMap::new();
+        let mut core = 0;
+        while let Some(MinScored { cost: degree, item: node }) = frontier.pop() {
+            if cores.contains_key(&node) || degrees[&node] != degree {
+                continue;
+            }
+            core = core.max(degree);
+            cores.insert(node, core);
+            for neighbor in &adjacency[&node] {
+                if cores.contains_key(neighbor) {
+                    continue;
+                }
+                if let Some(neighbor_degree) = degrees.get_mut(neighbor) {
+                    *neighbor_degree -= 1;
+                    frontier.push(MinScored { cost: *neighbor_degree, item: *neighbor });
+                }
+            }
+        }
+
+        cores
+    }
+
+    /// Returns the number of connections crossing a cut of the graph, i.e. with exactly one
+    /// endpoint on one side of it, in either direction.
+    /// # Arguments
+    /// * `side_a` - the keys of the nodes on one side of the cut.
+    fn cut_size(&'a self, side_a: &HashSet<K>) -> usize {
+        self.node_keys()
+            .map(|source| {
+                let in_a = side_a.contains(&source);
+                self.get_edges(&source)
+                    .into_iter()
+                    .flatten()
+                    .filter(|destination| side_a.contains(destination) != in_a)
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Returns a maximum matching between two sides of a bipartite graph, i.e. as many
+    /// `(left, right)` pairs of connected nodes as possible without using any node twice, using
+    /// augmenting paths. Every edge is treated as undirected, and edges that don't join the two
+    /// sides are ignored. Pairs are returned in key order of their left node.
+    /// # Arguments
+    /// * `left` - the keys of the nodes on one side.
+    /// * `right` - the keys of the nodes on the other side.
+    fn maximum_bipartite_matching(&'a self, left: &HashSet<K>, right: &HashSet<K>) -> Vec<(K, K)> {
+        let left_keys: Vec<K> = self.node_keys().filter(|key| left.contains(key)).collect();
+        let mut adjacency: HashMap<K, Vec<K>> = simple_adjacency(self);
+        adjacency.retain(|key, _| left.contains(key));
+        for candidates in adjacency.values_mut() {
+            candidates.retain(|candidate| right.contains(candidate) && !left.contains(candidate));
+        }
+
+        let mut matched: HashMap<K, K> = HashMap::new();
+        for node in &left_keys {
+            augment(&adjacency, *node, &mut matched, &mut HashSet::new());
+        }
+
+        let partners: HashMap<K, K> = matched.into_iter().map(|(r, l)| (l, r)).collect();
+        left_keys.into_iter()
+            .filter_map(|key| partners.get(&key).map(|partner| (key, *partner)))
+            .collect()
+    }
+
+    /// Returns the ordered pairs of distinct nodes within a subset of the graph that aren't
+    /// connected, in key order. Keys that aren't nodes in the graph are ignored.
+    /// # Arguments
+    /// * `keys` - the keys of the nodes in the subset.
+    fn complement_within(&'a self, keys: &HashSet<K>) -> Vec<(K, K)> {
+        let subset: Vec<K> = self.node_keys().filter(|key| keys.contains(key)).collect();
+        let mut complement: Vec<(K, K)> = vec![];
+        for source in &subset {
+            let connected: HashSet<&K> = self.get_edges(source).into_iter().flatten().collect();
+            for destination in &subset {
+                if source != destination && !connected.contains(destination) {
+                    complement.push((*source, *destination));
+                }
+            }
+        }
+        complement
+    }
+}
+
+impl<'a, T, K, V> AnalyzableGraph<'a, K, V> for T
+where
+    T: Graph<'a, K, V>,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adjacency_list_graph::AdjacencyListGraph;
+    use crate::weighted_graph::WeightedGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_is_bipartite_weighted() {
+        let mut odd_cycle: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        odd_cycle.add_weighted_connection(&0, &1, 2);
+        odd_cycle.add_weighted_connection(&1, &2, 4);
+        odd_cycle.add_weighted_connection(&2, &0, 6);
+        assert!(!odd_cycle.is_bipartite());
+
+        let mut even_cycle: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        even_cycle.add_weighted_connection(&0, &1, 3);
+        even_cycle.add_weighted_connection(&1, &2, 5);
+        even_cycle.add_weighted_connection(&2, &3, 7);
+        even_cycle.add_weighted_connection(&3, &0, 9);
+        assert!(even_cycle.is_bipartite());
+    }
+
+    #[test]
+    fn test_has_odd_cycle() {
+        let mut triangle: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        triangle.add_connection(&0, &1);
+        triangle.add_connection(&1, &2);
+        triangle.add_connection(&2, &0);
+        assert!(triangle.has_odd_cycle());
+
+        let mut square: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        square.add_connection(&0, &1);
+        square.add_connection(&1, &2);
+        square.add_connection(&2, &3);
+        square.add_connection(&3, &0);
+        assert!(!square.has_odd_cycle());
+    }
+
+    #[test]
+    fn test_is_regular() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &0);
+        assert_eq!(graph.is_regular(), Some(1));
+
+        graph.add_connection(&0, &2);
+        assert_eq!(graph.is_regular(), None);
+
+        let empty: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(vec![]);
+        assert_eq!(empty.is_regular(), Some(0));
+    }
+
+    #[test]
+    fn test_degree_assortativity() {
+        let mut star: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("center"),
+                String::from("leaf-1"),
+                String::from("leaf-2"),
+                String::from("leaf-3"),
+            ]
+        );
+        assert_eq!(star.degree_assortativity(), None);
+
+        star.add_connection(&0, &1);
+        star.add_connection(&0, &2);
+        star.add_connection(&0, &3);
+        let assortativity = star.degree_assortativity().unwrap();
+        assert!(assortativity < 0.0);
+        assert!((assortativity + 1.0).abs() < 1e-9);
+
+        // A connection back along an existing one is the same undirected edge.
+        star.add_connection(&1, &0);
+        assert_eq!(star.degree_assortativity(), Some(assortativity));
+
+        star.add_connection(&1, &2);
+        star.add_connection(&2, &1);
+        let with_pair = star.degree_assortativity().unwrap();
+        star.remove_connection(&2, &1);
+        assert_eq!(star.degree_assortativity(), Some(with_pair));
+    }
+
+    #[test]
+    fn test_complement_within() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &3);
+        graph.add_connection(&3, &2);
+
+        let complement = graph.complement_within(&HashSet::from([0, 1, 2, 9]));
+        assert_eq!(complement, vec![(0, 2), (1, 0), (1, 2), (2, 0), (2, 1)]);
+    }
+
+    #[test]
+    fn test_spanning_tree_count() {
+        let mut triangle: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        triangle.add_connection(&0, &1);
+        triangle.add_connection(&1, &2);
+        triangle.add_connection(&2, &0);
+        triangle.add_connection(&0, &2);
+        assert_eq!(triangle.spanning_tree_count(), Some(3));
+
+        let mut square: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        square.add_connection(&0, &1);
+        square.add_connection(&1, &2);
+        square.add_connection(&2, &3);
+        assert_eq!(square.spanning_tree_count(), Some(1));
+        square.add_connection(&3, &0);
+        assert_eq!(square.spanning_tree_count(), Some(4));
+
+        square.insert(String::from("node-5"));
+        assert_eq!(square.spanning_tree_count(), Some(0));
+    }
+
+    #[test]
+    fn test_edge_betweenness() {
+        let mut barbell: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=6).map(|index| format!("node-{}", index)).collect()
+        );
+        for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+            barbell.add_connection(&a, &b);
+            barbell.add_connection(&b, &a);
+        }
+
+        let betweenness = barbell.edge_betweenness();
+        assert_eq!(betweenness.len(), 14);
+        // Every path between the two triangles crosses the bridge.
+        assert_eq!(betweenness[&(2, 3)], 9.0);
+        assert_eq!(betweenness[&(3, 2)], 9.0);
+        for (edge, value) in &betweenness {
+            if *edge != (2, 3) && *edge != (3, 2) {
+                assert!(*value < betweenness[&(2, 3)]);
+            }
+        }
+        assert_eq!(betweenness[&(0, 1)], 1.0);
+    }
+
+    #[test]
+    fn test_core_numbers() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=6).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &0);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&3, &0);
+        graph.add_connection(&1, &4);
+        graph.add_connection(&4, &4);
+
+        assert_eq!(
+            graph.core_numbers(),
+            HashMap::from([(0, 2), (1, 2), (2, 2), (3, 1), (4, 1), (5, 0)])
+        );
+    }
+
+    #[test]
+    fn test_maximum_bipartite_matching() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=7).map(|index| format!("node-{}", index)).collect()
+        );
+        // Workers 0, 1, 2 and 3, jobs 4, 5 and 6.
+        graph.add_connection(&0, &4);
+        graph.add_connection(&0, &5);
+        graph.add_connection(&1, &4);
+        graph.add_connection(&6, &2);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&0, &1);
+        let left = HashSet::from([0, 1, 2, 3]);
+        let right = HashSet::from([4, 5, 6]);
+
+        let matching = graph.maximum_bipartite_matching(&left, &right);
+        assert_eq!(matching.len(), 3);
+        assert!(matching.contains(&(0, 5)));
+        assert!(matching.contains(&(2, 6)));
+        let mut used: HashSet<u16> = HashSet::new();
+        assert!(matching.iter().all(|(a, b)| used.insert(*a) && used.insert(*b)));
+        assert!(matching.iter().all(|(a, b)| graph.has_edge(a, b) || graph.has_edge(b, a)));
+    }
+
+    #[test]
+    fn test_maximum_bipartite_matching_long_augmenting_path() {
+        // Left nodes 0..size each connect to right nodes `size + 1 + key` and `size + 2 + key`,
+        // and the last left node `size` only connects to the right node taken by left node 0,
+        // so matching it has to shift every earlier pair along one alternating path.
+        let size: u32 = 100_000;
+        let mut graph: AdjacencyListGraph<u32, u32> =
+            AdjacencyListGraph::new((0..2 * size + 2).collect());
+        for key in 0..size {
+            graph.add_connection(&key, &(size + 1 + key));
+            graph.add_connection(&key, &(size + 2 + key));
+        }
+        graph.add_connection(&size, &(size + 1));
+        let left: HashSet<u32> = (0..=size).collect();
+        let right: HashSet<u32> = (size + 1..2 * size + 2).collect();
+
+        let matching = graph.maximum_bipartite_matching(&left, &right);
+        assert_eq!(matching.len(), size as usize + 1);
+        assert_eq!(matching[0], (0, size + 2));
+        assert_eq!(matching[size as usize], (size, size + 1));
+    }
+}
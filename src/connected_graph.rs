@@ -0,0 +1,579 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+use crate::analyzable_graph::{simple_adjacency, undirected_adjacency};
+use crate::flow_graph::FlowNetwork;
+use crate::graph::Graph;
+
+/// Counts the connected components of an undirected adjacency, leaving out the `excluded` node.
+fn count_components<K>(adjacency: &HashMap<K, Vec<K>>, excluded: Option<&K>) -> usize
+where
+    K: Copy + Hash + Eq
+{
+    let mut visited: HashSet<K> = excluded.into_iter().copied().collect();
+    let mut components = 0;
+
+    for start in adjacency.keys() {
+        if !visited.insert(*start) {
+            continue;
+        }
+        components += 1;
+        let mut stack = vec![*start];
+        while let Some(node) = stack.pop() {
+            for neighbor in &adjacency[&node] {
+                if visited.insert(*neighbor) {
+                    stack.push(*neighbor);
+                }
+            }
+        }
+    }
+
+    components
+}
+
+/// Returns whether every connection in a graph goes from a component to itself or to a later
+/// component in the list.
+fn is_topological_component_order<'a, G, K, V>(graph: &'a G, components: &[Vec<K>]) -> bool
+where
+    G: Graph<'a, K, V> + ?Sized,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+    let positions: HashMap<K, usize> = components.iter()
+        .enumerate()
+        .flat_map(|(index, component)| component.iter().map(move |key| (*key, index)))
+        .collect();
+    positions.iter().all(|(key, position)| {
+        graph.get_edges(key)
+            .into_iter()
+            .flatten()
+            .all(|edge| positions.get(edge).is_none_or(|other| other >= position))
+    })
+}
+
+pub trait ConnectedGraph<'a, K, V>: Graph<'a, K, V>
+where
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+    /// Returns whether removing a node would split the rest of the graph into more connected
+    /// components than the graph has, treating every edge as undirected.
+    /// # Arguments
+    /// * `key` - the key of the node to check.
+    fn is_articulation_point(&'a self, key: &K) -> bool {
+        if self.get_value(key).is_none() {
+            return false;
+        }
+        let adjacency = undirected_adjacency(self);
+        count_components(&adjacency, Some(key)) > count_components(&adjacency, None)
+    }
+
+    /// Returns whether there are two paths from one node to another in the graph that share no
+    /// nodes other than their endpoints. Each node is split into an entry and an exit joined by a
+    /// unit capacity, so the maximum flow between the nodes counts node-disjoint paths. Parallel
+    /// connections count as a single path.
+    /// # Arguments
+    /// * `source` - the key of the node the paths start from.
+    /// * `destination` - the key of the node the paths end at.
+    fn has_two_disjoint_paths(&'a self, source: &K, destination: &K) -> bool {
+        let indices: HashMap<K, usize> = self.node_keys()
+            .enumerate()
+            .map(|(index, key)| (key, index))
+            .collect();
+        let endpoints = (indices.get(source), indices.get(destination));
+        let (Some(&source), Some(&destination)) = endpoints else {
+            return false;
+        };
+        if source == destination {
+            return false;
+        }
+
+        // Node `i` enters at `2 * i` and leaves at `2 * i + 1`.
+        let mut network: FlowNetwork<i64> = FlowNetwork::new(indices.len() * 2);
+        let mut connected: HashSet<(usize, usize)> = HashSet::new();
+        for (key, &index) in &indices {
+            let capacity = if index == source || index == destination { 2 } else { 1 };
+            network.add_edge(2 * index, 2 * index + 1, capacity, 0);
+            for edge in self.get_edges(key).into_iter().flatten() {
+                if let Some(&next) = indices.get(edge) {
+                    if connected.insert((index, next)) {
+                        network.add_edge(2 * index + 1, 2 * next, 1, 0);
+                    }
+                }
+            }
+        }
+
+        // Every cost is zero, so there is no negative cycle.
+        network.min_cost_max_flow(2 * source, 2 * destination + 1)
+            .is_ok_and(|(flow, _)| flow >= 2)
+    }
+
+    /// Returns the connected components of the graph, treating every edge as undirected, so a
+    /// connection in either direction joins two nodes. Isolated nodes are components of their
+    /// own. Components are ordered by their first node, and the nodes in each component are in
+    /// key order.
+    fn connected_components(&'a self) -> Vec<Vec<K>> {
+        let adjacency = undirected_adjacency(self);
+        let mut component_of: HashMap<K, usize> = HashMap::new();
+        let mut components: Vec<Vec<K>> = vec![];
+
+        for start in self.node_keys() {
+            if component_of.contains_key(&start) {
+                continue;
+            }
+            let component = components.len();
+            components.push(vec![]);
+            component_of.insert(start, component);
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for neighbor in adjacency.get(&node).into_iter().flatten() {
+                    if adjacency.contains_key(neighbor) && !component_of.contains_key(neighbor) {
+                        component_of.insert(*neighbor, component);
+                        stack.push(*neighbor);
+                    }
+                }
+            }
+        }
+
+        for key in self.node_keys() {
+            components[component_of[&key]].push(key);
+        }
+        components
+    }
+
+    /// Returns the number of connected components in the graph, treating every edge as
+    /// undirected.
+    fn component_count(&'a self) -> usize {
+        count_components(&undirected_adjacency(self), None)
+    }
+
+    /// Returns a uniformly random spanning tree of the graph, treating every edge as undirected,
+    /// using Wilson's algorithm: a loop-erased random walk from each node not yet in the tree
+    /// until it reaches the tree. Every edge is returned as `(child, parent)`, with the tree
+    /// rooted at the first node. Returns an empty list if the graph isn't connected.
+    /// # Arguments
+    /// * `rng` - the random number generator to walk the graph with.
+    #[cfg(feature = "rand")]
+    fn random_spanning_tree(&'a self, rng: &mut impl Rng) -> Vec<(K, K)> {
+        let adjacency = simple_adjacency(self);
+        let keys: Vec<K> = self.node_keys().collect();
+        let Some(&root) = keys.first() else {
+            return vec![];
+        };
+        if count_components(&adjacency, None) != 1 {
+            return vec![];
+        }
+
+        let mut in_tree: HashSet<K> = HashSet::from([root]);
+        let mut next: HashMap<K, K> = HashMap::new();
+        let mut tree: Vec<(K, K)> = Vec::with_capacity(keys.len() - 1);
+        for start in &keys {
+            // Only the last step taken out of each node is kept, which erases the walk's loops.
+            let mut node = *start;
+            while !in_tree.contains(&node) {
+                let neighbors = &adjacency[&node];
+                let step = neighbors[rng.gen_range(0..neighbors.len())];
+                next.insert(node, step);
+                node = step;
+            }
+            let mut node = *start;
+            while in_tree.insert(node) {
+                let parent = next[&node];
+                tree.push((node, parent));
+                node = parent;
+            }
+        }
+        tree
+    }
+
+    /// Returns every bridge in the graph, i.e. every edge whose removal would split the graph
+    /// into more connected components, treating every edge as undirected. Connections in both
+    /// directions between two nodes count as a single edge. Each bridge is returned as a pair of
+    /// keys, in the order the edge was first crossed by a depth-first search in key order.
+    fn bridges(&'a self) -> Vec<(K, K)> {
+        let adjacency = simple_adjacency(self);
+        let mut discovery: HashMap<K, usize> = HashMap::new();
+        let mut low: HashMap<K, usize> = HashMap::new();
+        let mut bridges: Vec<(K, K)> = vec![];
+
+        for start in self.node_keys() {
+            if discovery.contains_key(&start) {
+                continue;
+            }
+            discovery.insert(start, discovery.len());
+            low.insert(start, discovery[&start]);
+            let mut stack: Vec<(K, Option<K>, usize)> = vec![(start, None, 0)];
+
+            while let Some((node, parent, index)) = stack.last_mut() {
+                let (node, parent) = (*node, *parent);
+                if let Some(&next) = adjacency[&node].get(*index) {
+                    *index += 1;
+                    if Some(next) == parent {
+                        continue;
+                    }
+                    if let Some(&order) = discovery.get(&next) {
+                        low.insert(node, low[&node].min(order));
+                    } else {
+                        discovery.insert(next, discovery.len());
+                        low.insert(next, discovery[&next]);
+                        stack.push((next, Some(node), 0));
+                    }
+                } else {
+                    stack.pop();
+                    if let Some(parent) = parent {
+                        let node_low = low[&node];
+                        low.insert(parent, low[&parent].min(node_low));
+                        if node_low > discovery[&parent] {
+                            bridges.push((parent, node));
+                        }
+                    }
+                }
+            }
+        }
+
+        bridges
+    }
+
+    /// Returns the 2-edge-connected components of the graph, i.e. the groups of nodes that stay
+    /// connected after removing every bridge, treating every edge as undirected. Components are
+    /// ordered by their first node, and the nodes in each component are in key order.
+    fn two_edge_connected_components(&'a self) -> Vec<Vec<K>> {
+        let adjacency = simple_adjacency(self);
+        let bridges: HashSet<(K, K)> = self.bridges()
+            .into_iter()
+            .flat_map(|(a, b)| [(a, b), (b, a)])
+            .collect();
+        let mut component_of: HashMap<K, usize> = HashMap::new();
+        let mut components: Vec<Vec<K>> = vec![];
+
+        for start in self.node_keys() {
+            if component_of.contains_key(&start) {
+                continue;
+            }
+            let component = components.len();
+            components.push(vec![]);
+            component_of.insert(start, component);
+            let mut stack = vec![start];
+            while let Some(node) = stack.pop() {
+                for neighbor in &adjacency[&node] {
+                    if !bridges.contains(&(node, *neighbor)) && !component_of.contains_key(neighbor) {
+                        component_of.insert(*neighbor, component);
+                        stack.push(*neighbor);
+                    }
+                }
+            }
+        }
+
+        for key in self.node_keys() {
+            components[component_of[&key]].push(key);
+        }
+        components
+    }
+
+    /// Returns the strongly connected components of the graph using Tarjan's algorithm, where
+    /// every node in a component can reach every other node in it. Components are returned in
+    /// reverse topological order of the condensation, so no component has a connection into a
+    /// component returned after it, and the nodes in each component are in the order they were
+    /// discovered. The search uses an explicit stack, so it doesn't recurse on large graphs.
+    fn strongly_connected_components(&'a self) -> Vec<Vec<K>> {
+        let mut indices: HashMap<K, usize> = HashMap::new();
+        let mut low: HashMap<K, usize> = HashMap::new();
+        let mut on_stack: HashSet<K> = HashSet::new();
+        let mut stack: Vec<K> = vec![];
+        let mut components: Vec<Vec<K>> = vec![];
+
+        for start in self.node_keys() {
+            if indices.contains_key(&start) {
+                continue;
+            }
+            let Some(edges) = self.get_edges(&start) else {
+                continue;
+            };
+            indices.insert(start, indices.len());
+            low.insert(start, indices[&start]);
+            on_stack.insert(start);
+            stack.push(start);
+            let mut search: Vec<(K, Self::EdgeIterator)> = vec![(start, edges)];
+
+            while let Some((node, edges)) = search.last_mut() {
+                let node = *node;
+                match edges.next().copied() {
+                    Some(next) if !indices.contains_key(&next) => {
+                        if let Some(next_edges) = self.get_edges(&next) {
+                            indices.insert(next, indices.len());
+                            low.insert(next, indices[&next]);
+                            on_stack.insert(next);
+                            stack.push(next);
+                            search.push((next, next_edges));
+                        }
+                    }
+                    Some(next) if on_stack.contains(&next) => {
+                        low.insert(node, low[&node].min(indices[&next]));
+                    }
+                    Some(_) => {}
+                    None => {
+                        search.pop();
+                        if let Some((parent, _)) = search.last() {
+                            low.insert(*parent, low[parent].min(low[&node]));
+                        }
+                        if low[&node] == indices[&node] {
+                            let mut component: Vec<K> = vec![];
+                            while let Some(member) = stack.pop() {
+                                on_stack.remove(&member);
+                                component.push(member);
+                                if member == node {
+                                    break;
+                                }
+                            }
+                            component.reverse();
+                            components.push(component);
+                        }
+                    }
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Returns the strongly connected components of the graph in topological order of the
+    /// condensation, so every connection between two components goes from an earlier component
+    /// to a later one.
+    fn scc_topological_order(&'a self) -> Vec<Vec<K>> {
+        let mut components = self.strongly_connected_components();
+        components.reverse();
+        debug_assert!(is_topological_component_order(self, &components));
+        components
+    }
+}
+
+impl<'a, T, K, V> ConnectedGraph<'a, K, V> for T
+where
+    T: Graph<'a, K, V>,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adjacency_list_graph::AdjacencyListGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_is_articulation_point() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &0);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &2);
+        assert!(graph.is_articulation_point(&2));
+        assert!(!graph.is_articulation_point(&0));
+        assert!(!graph.is_articulation_point(&4));
+        assert!(!graph.is_articulation_point(&9));
+
+        let isolated = graph.insert(String::from("node-6"));
+        assert!(!graph.is_articulation_point(&isolated));
+        assert!(graph.is_articulation_point(&2));
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &0);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &2);
+        assert_eq!(graph.connected_components(), vec![vec![0, 1, 2, 3, 4]]);
+        assert_eq!(graph.component_count(), 1);
+
+        graph.insert(String::from("node-6"));
+        graph.insert(String::from("node-7"));
+        graph.insert(String::from("node-8"));
+        graph.add_connection(&7, &6);
+        assert_eq!(
+            graph.connected_components(),
+            vec![vec![0, 1, 2, 3, 4], vec![5], vec![6, 7]]
+        );
+        assert_eq!(graph.component_count(), 3);
+    }
+
+    #[test]
+    fn test_two_edge_connected_components() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=6).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &0);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &5);
+        graph.add_connection(&5, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &2);
+
+        assert_eq!(graph.bridges(), vec![(2, 3)]);
+        assert_eq!(
+            graph.two_edge_connected_components(),
+            vec![vec![0, 1, 2], vec![3, 4, 5]]
+        );
+
+        // The bowtie's triangles share a node rather than a bridge.
+        let mut bowtie: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        bowtie.add_connection(&0, &1);
+        bowtie.add_connection(&1, &2);
+        bowtie.add_connection(&2, &0);
+        bowtie.add_connection(&2, &3);
+        bowtie.add_connection(&3, &4);
+        bowtie.add_connection(&4, &2);
+        assert!(bowtie.bridges().is_empty());
+        assert_eq!(bowtie.two_edge_connected_components(), vec![vec![0, 1, 2, 3, 4]]);
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=6).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &0);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &2);
+
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![vec![2, 3, 4], vec![0, 1], vec![5]]
+        );
+    }
+
+    #[test]
+    fn test_strongly_connected_components_long_cycle() {
+        let size: u32 = 100_000;
+        let mut graph: AdjacencyListGraph<u32, u32> = AdjacencyListGraph::new((0..size).collect());
+        for key in 0..size {
+            graph.add_connection(&key, &((key + 1) % size));
+        }
+
+        let components = graph.strongly_connected_components();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), size as usize);
+    }
+
+    #[test]
+    fn test_scc_topological_order() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=5).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &3);
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &0);
+        graph.add_connection(&2, &3);
+
+        assert_eq!(graph.scc_topological_order(), vec![vec![0, 1, 2], vec![3, 4]]);
+
+        graph.remove_connection(&2, &3);
+        graph.add_connection(&4, &0);
+        assert_eq!(graph.scc_topological_order(), vec![vec![3, 4], vec![0, 1, 2]]);
+        assert!(is_topological_component_order(&graph, &graph.scc_topological_order()));
+        assert!(!is_topological_component_order(&graph, &graph.strongly_connected_components()));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_random_spanning_tree() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &0);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &2);
+        let tree = graph.random_spanning_tree(&mut StdRng::seed_from_u64(7));
+        assert_eq!(tree.len(), graph.node_count() - 1);
+        assert!(tree.iter().all(|(a, b)| graph.has_edge(a, b) || graph.has_edge(b, a)));
+
+        let mut as_graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=5).map(|index| format!("node-{}", index)).collect()
+        );
+        for (child, parent) in &tree {
+            as_graph.add_connection(child, parent);
+        }
+        // A connected graph with one edge fewer than it has nodes has no cycles.
+        assert_eq!(as_graph.component_count(), 1);
+        assert_eq!(tree, graph.random_spanning_tree(&mut StdRng::seed_from_u64(7)));
+
+        let mut disconnected = graph;
+        disconnected.insert(String::from("node-6"));
+        assert!(disconnected.random_spanning_tree(&mut StdRng::seed_from_u64(7)).is_empty());
+    }
+
+    #[test]
+    fn test_has_two_disjoint_paths() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            (1..=6).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&3, &5);
+        graph.add_connection(&4, &5);
+
+        assert!(graph.has_two_disjoint_paths(&0, &3));
+        assert!(graph.has_two_disjoint_paths(&3, &5));
+        // Every path from 0 to 5 passes through the cut vertex 3.
+        assert!(!graph.has_two_disjoint_paths(&0, &5));
+        assert!(!graph.has_two_disjoint_paths(&5, &0));
+
+        graph.add_connection(&2, &5);
+        assert!(graph.has_two_disjoint_paths(&0, &5));
+    }
+}
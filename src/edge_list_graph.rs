@@ -0,0 +1,322 @@
+use num::traits::{PrimInt, Zero};
+
+use crate::error::GraphError;
+use crate::graph::Graph;
+use crate::weighted_graph::{NoWeight, WeightedGraph};
+
+/// A graph that stores its connections in a single flat list of `(source, destination, weight)`
+/// triples, which makes iterating over every connection O(E) but finding the connections of a
+/// single node a scan of the whole list.
+#[derive(Debug)]
+pub struct EdgeListGraph<K, V, W = NoWeight>
+where
+    K: PrimInt + Copy,
+    V: PartialEq,
+    W: PartialOrd + Zero + Copy
+{
+    // Removed nodes leave a `None` tombstone behind so that the keys of other nodes stay valid.
+    nodes: Vec<Option<V>>,
+    edges: Vec<(K, K, W)>,
+    // Indices of tombstoned slots, reused by `insert` before the graph grows.
+    free: Vec<usize>
+}
+
+impl<K, V, W> EdgeListGraph<K, V, W>
+where
+    K: PrimInt + Copy,
+    V: PartialEq,
+    W: PartialOrd + Zero + Copy
+{
+    pub fn new(nodes: Vec<V>) -> EdgeListGraph<K, V, W> {
+        EdgeListGraph {
+            nodes: nodes.into_iter().map(Some).collect(),
+            edges: vec![],
+            free: vec![]
+        }
+    }
+
+    /// Returns every connection in the graph as `(source, destination, weight)`, in the order
+    /// they were added. Unlike `Graph::all_edges`, this walks the edge list once instead of
+    /// scanning it for every node.
+    pub fn all_edges(&self) -> impl Iterator<Item = (&K, &K, &W)> {
+        self.edges.iter().map(|(source, destination, weight)| (source, destination, weight))
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        key.to_usize()
+            .and_then(|index| self.nodes.get(index))
+            .is_some_and(Option::is_some)
+    }
+}
+
+pub struct EdgeDestinationIterator<'a, K, W> {
+    iter: std::slice::Iter<'a, (K, K, W)>,
+    source: K
+}
+
+impl<'a, K, W> Iterator for EdgeDestinationIterator<'a, K, W>
+where
+    K: Eq
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let source = &self.source;
+        self.iter.find(|edge| &edge.0 == source).map(|edge| &edge.1)
+    }
+}
+
+impl<'a, K, W> DoubleEndedIterator for EdgeDestinationIterator<'a, K, W>
+where
+    K: Eq
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let source = &self.source;
+        self.iter.rfind(|edge| &edge.0 == source).map(|edge| &edge.1)
+    }
+}
+
+pub struct WeightedEdgeIterator<'a, K, W> {
+    iter: std::slice::Iter<'a, (K, K, W)>,
+    source: K
+}
+
+impl<'a, K, W> Iterator for WeightedEdgeIterator<'a, K, W>
+where
+    K: Eq
+{
+    type Item = (&'a K, &'a W);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let source = &self.source;
+        self.iter.find(|edge| &edge.0 == source).map(|edge| (&edge.1, &edge.2))
+    }
+}
+
+impl<'a, K, W> DoubleEndedIterator for WeightedEdgeIterator<'a, K, W>
+where
+    K: Eq
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let source = &self.source;
+        self.iter.rfind(|edge| &edge.0 == source).map(|edge| (&edge.1, &edge.2))
+    }
+}
+
+pub struct NodeKeyIterator<'a, K, V> {
+    iter: std::iter::Enumerate<std::slice::Iter<'a, Option<V>>>,
+    _key: std::marker::PhantomData<K>,
+}
+
+impl<'a, K, V> Iterator for NodeKeyIterator<'a, K, V>
+where
+    K: PrimInt
+{
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .find(|(_, node)| node.is_some())
+            .map(|(index, _)| K::from(index).unwrap())
+    }
+}
+
+impl<'a, K, V, W> Graph<'a, K, V> for EdgeListGraph<K, V, W>
+where
+    K: PrimInt + Copy + 'a,
+    V: PartialEq + 'a,
+    W: PartialOrd + Zero + Copy + 'a
+{
+    type EdgeIterator = EdgeDestinationIterator<'a, K, W>;
+    type NodeKeyIterator = NodeKeyIterator<'a, K, V>;
+
+    fn try_insert(&mut self, value: V) -> Result<K, GraphError> {
+        if let Some(index) = self.free.pop() {
+            self.nodes[index] = Some(value);
+            return Ok(K::from(index).unwrap());
+        }
+
+        let key = K::from(self.nodes.len()).ok_or(GraphError::CapacityExceeded)?;
+        self.nodes.push(Some(value));
+        Ok(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let index = key.to_usize()?;
+        let value = self.nodes.get_mut(index)?.take()?;
+
+        self.edges.retain(|(source, destination, _)| source != key && destination != key);
+        self.free.push(index);
+        Some(value)
+    }
+
+    fn add_connection(&mut self, source: &K, destination: &K) -> bool {
+        self.add_weighted_connection(source, destination, W::zero())
+    }
+
+    fn remove_connection(&mut self, source: &K, destination: &K) -> bool {
+        if let Some(index) = self.edges.iter().position(|e| &e.0 == source && &e.1 == destination) {
+            self.edges.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn remove_incoming(&mut self, key: &K) -> usize {
+        let before = self.edges.len();
+        self.edges.retain(|(_, destination, _)| destination != key);
+        before - self.edges.len()
+    }
+
+    fn remove_outgoing(&mut self, key: &K) -> usize {
+        let before = self.edges.len();
+        self.edges.retain(|(source, _, _)| source != key);
+        before - self.edges.len()
+    }
+
+    fn get(&'a self, key: &K) -> Option<(&'a V, Self::EdgeIterator)> {
+        Some((self.get_value(key)?, self.get_edges(key)?))
+    }
+
+    fn get_value(&self, key: &K) -> Option<&V> {
+        self.nodes.get(key.to_usize()?)?.as_ref()
+    }
+
+    fn get_edges(&'a self, key: &K) -> Option<Self::EdgeIterator> {
+        self.get_value(key)?;
+        Some(EdgeDestinationIterator {
+            iter: self.edges.iter(),
+            source: *key
+        })
+    }
+
+    fn node_count(&'a self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    fn edge_count(&'a self) -> usize {
+        self.edges.len()
+    }
+
+    fn node_keys(&'a self) -> Self::NodeKeyIterator {
+        NodeKeyIterator {
+            iter: self.nodes.iter().enumerate(),
+            _key: std::marker::PhantomData
+        }
+    }
+}
+
+impl<'a, K, V, W> WeightedGraph<'a, K, V, W> for EdgeListGraph<K, V, W>
+where
+    K: PrimInt + Copy + 'a,
+    V: PartialEq + 'a,
+    W: PartialOrd + Zero + Copy + 'a
+{
+    type WeightedEdgeIterator = WeightedEdgeIterator<'a, K, W>;
+
+    fn add_weighted_connection(&mut self, source: &K, destination: &K, weight: W) -> bool {
+        if !self.contains(source) || !self.contains(destination) {
+            return false;
+        }
+        self.edges.push((*source, *destination, weight));
+        true
+    }
+
+    fn remove_weighted_connection(&mut self, source: &K, destination: &K, weight: &W) -> bool
+    where
+        W: PartialEq
+    {
+        let position = self.edges
+            .iter()
+            .position(|e| &e.0 == source && &e.1 == destination && &e.2 == weight);
+        if let Some(index) = position {
+            self.edges.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn get_weighted(&'a self, key: &K) -> Option<(&'a V, Self::WeightedEdgeIterator)> {
+        Some((self.get_value(key)?, self.get_weighted_edges(key)?))
+    }
+
+    fn get_weighted_edges(&'a self, key: &K) -> Option<Self::WeightedEdgeIterator> {
+        self.get_value(key)?;
+        Some(WeightedEdgeIterator {
+            iter: self.edges.iter(),
+            source: *key
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::weighted_graph::total_weight;
+
+    use super::*;
+
+    #[test]
+    fn test_getters() {
+        let mut graph: EdgeListGraph<u16, String, i32> = EdgeListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 3);
+        graph.add_weighted_connection(&1, &2, 1);
+        graph.add_weighted_connection(&0, &2, 1);
+        graph.add_weighted_connection(&2, &3, 2);
+        assert!(!graph.add_connection(&0, &9));
+
+        assert!(graph.get_edges(&0).unwrap().eq(vec![&1, &2]));
+        assert!(graph.get_edges(&0).unwrap().rev().eq(vec![&2, &1]));
+        assert!(graph.get_weighted_edges(&2).unwrap().eq(vec![(&3, &2)]));
+        assert_eq!(
+            graph.all_edges().collect::<Vec<_>>(),
+            vec![(&0, &1, &3), (&1, &2, &1), (&0, &2, &1), (&2, &3, &2)]
+        );
+        assert_eq!(total_weight(&graph.minimum_spanning_tree_kruskal()), 4);
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut graph: EdgeListGraph<u16, String> = EdgeListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &1);
+
+        assert_eq!(graph.remove(&1), Some(String::from("node-2")));
+        assert_eq!(graph.edge_count(), 0);
+        assert_eq!(graph.node_count(), 2);
+        assert!(graph.get_edges(&1).is_none());
+        assert_eq!(graph.insert(String::from("node-4")), 1);
+
+        graph.add_connection(&0, &1);
+        graph.add_connection(&2, &1);
+        graph.add_connection(&1, &0);
+        assert_eq!(graph.remove_incoming(&1), 2);
+        assert_eq!(graph.remove_outgoing(&1), 1);
+        assert!(!graph.remove_connection(&1, &0));
+    }
+
+    #[test]
+    fn test_try_insert_capacity() {
+        let mut graph: EdgeListGraph<u8, usize> = EdgeListGraph::new((0..256).collect());
+        assert_eq!(graph.try_insert(256), Err(GraphError::CapacityExceeded));
+        assert_eq!(graph.node_count(), 256);
+
+        graph.remove(&3);
+        assert_eq!(graph.try_insert(256), Ok(3));
+    }
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::fmt;
+
+/// The errors returned by fallible graph operations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    /// The operation requires an acyclic graph, but the graph contains a cycle.
+    Cycle,
+    /// The operation requires a graph without negative-weight cycles, but the graph contains one.
+    NegativeCycle,
+    /// A key doesn't refer to a node in the graph.
+    InvalidKey,
+    /// The graph can't hold any more nodes, since the key type can't represent their keys.
+    CapacityExceeded
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::Cycle => write!(f, "the graph contains a cycle"),
+            GraphError::NegativeCycle => write!(f, "the graph contains a negative-weight cycle"),
+            GraphError::InvalidKey => write!(f, "the key doesn't refer to a node in the graph"),
+            GraphError::CapacityExceeded => write!(f, "the key type can't represent any more nodes")
+        }
+    }
+}
+
+impl Error for GraphError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let messages: Vec<String> = [
+            GraphError::Cycle,
+            GraphError::NegativeCycle,
+            GraphError::InvalidKey,
+            GraphError::CapacityExceeded
+        ].iter().map(|error| error.to_string()).collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "the graph contains a cycle",
+                "the graph contains a negative-weight cycle",
+                "the key doesn't refer to a node in the graph",
+                "the key type can't represent any more nodes"
+            ]
+        );
+    }
+}
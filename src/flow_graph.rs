@@ -0,0 +1,232 @@
+use num::traits::Zero;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Mul, Neg, Sub};
+
+use crate::error::GraphError;
+use crate::weighted_graph::WeightedGraph;
+
+/// The node and edge index used to reach each node on a path through a `FlowNetwork`.
+type Predecessors = Vec<Option<(usize, usize)>>;
+
+#[derive(Debug, Copy, Clone)]
+struct ResidualEdge<W> {
+    destination: usize,
+    capacity: W,
+    cost: W,
+    reverse: usize
+}
+
+/// A residual network over node indices, used to compute flows.
+#[derive(Debug, Clone)]
+pub(crate) struct FlowNetwork<W> {
+    edges: Vec<Vec<ResidualEdge<W>>>
+}
+
+impl<W> FlowNetwork<W>
+where
+    W: PartialOrd + Zero + Copy + Sub<Output = W> + Mul<Output = W> + Neg<Output = W>
+{
+    pub(crate) fn new(size: usize) -> FlowNetwork<W> {
+        FlowNetwork { edges: vec![vec![]; size] }
+    }
+
+    /// Adds an edge with a capacity and a cost per unit of flow, along with its residual edge.
+    pub(crate) fn add_edge(&mut self, source: usize, destination: usize, capacity: W, cost: W) {
+        let reverse = self.edges[destination].len() + usize::from(source == destination);
+        let forward = self.edges[source].len();
+        self.edges[source].push(ResidualEdge { destination, capacity, cost, reverse });
+        self.edges[destination].push(ResidualEdge {
+            destination: source,
+            capacity: W::zero(),
+            cost: -cost,
+            reverse: forward
+        });
+    }
+
+    /// Finds the cheapest augmenting path with the Bellman-Ford algorithm, returning the cost of
+    /// the path along with the node and edge index used to reach each node, or
+    /// `GraphError::NegativeCycle` if a negative-cost cycle is reachable from the source.
+    fn cheapest_path(
+        &self,
+        source: usize,
+        sink: usize
+    ) -> Result<Option<(W, Predecessors)>, GraphError> {
+        let mut distances: Vec<Option<W>> = vec![None; self.edges.len()];
+        let mut previous: Predecessors = vec![None; self.edges.len()];
+        distances[source] = Some(W::zero());
+
+        // Without a negative cycle every distance is final after `n - 1` passes, so a change in
+        // pass `n` means there is one.
+        let mut settled = false;
+        for _ in 0..self.edges.len() {
+            let mut changed = false;
+            for (node, edges) in self.edges.iter().enumerate() {
+                let Some(distance) = distances[node] else {
+                    continue;
+                };
+                for (index, edge) in edges.iter().enumerate() {
+                    if edge.capacity <= W::zero() {
+                        continue;
+                    }
+                    let candidate = distance + edge.cost;
+                    if distances[edge.destination].is_none_or(|current| candidate < current) {
+                        distances[edge.destination] = Some(candidate);
+                        previous[edge.destination] = Some((node, index));
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                settled = true;
+                break;
+            }
+        }
+        if !settled {
+            return Err(GraphError::NegativeCycle);
+        }
+
+        Ok(distances[sink].map(|distance| (distance, previous)))
+    }
+
+    /// Pushes as much flow as possible from the source to the sink, always along the cheapest
+    /// remaining path, returning the total flow and its total cost, or
+    /// `GraphError::NegativeCycle` if the residual network has a negative-cost cycle.
+    pub(crate) fn min_cost_max_flow(
+        &mut self,
+        source: usize,
+        sink: usize
+    ) -> Result<(W, W), GraphError> {
+        let mut flow = W::zero();
+        let mut cost = W::zero();
+        if source == sink {
+            return Ok((flow, cost));
+        }
+
+        while let Some((path_cost, previous)) = self.cheapest_path(source, sink)? {
+            let mut bottleneck: Option<W> = None;
+            let mut node = sink;
+            while let Some((parent, index)) = previous[node] {
+                let capacity = self.edges[parent][index].capacity;
+                if bottleneck.is_none_or(|current| capacity < current) {
+                    bottleneck = Some(capacity);
+                }
+                node = parent;
+            }
+            let Some(bottleneck) = bottleneck else {
+                break;
+            };
+
+            let mut node = sink;
+            while let Some((parent, index)) = previous[node] {
+                let reverse = self.edges[parent][index].reverse;
+                self.edges[parent][index].capacity = self.edges[parent][index].capacity - bottleneck;
+                self.edges[node][reverse].capacity = self.edges[node][reverse].capacity + bottleneck;
+                node = parent;
+            }
+            flow = flow + bottleneck;
+            cost = cost + bottleneck * path_cost;
+        }
+
+        Ok((flow, cost))
+    }
+}
+
+pub trait FlowGraph<'a, K, V, W>: WeightedGraph<'a, K, V, W>
+where
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a,
+    W: PartialOrd + Zero + Copy + Sub<Output = W> + Mul<Output = W> + Neg<Output = W> + 'a
+{
+    /// Returns the maximum flow from a source node to a sink node in the graph, treating edge
+    /// weights as capacities, along with the lowest total cost of sending that flow. The flow is
+    /// found by repeatedly augmenting along the cheapest path in the residual graph. Returns
+    /// `GraphError::NegativeCycle` if the costs form a negative cycle the flow could go around.
+    /// # Arguments
+    /// * `source` - the key of the node the flow starts from.
+    /// * `sink` - the key of the node the flow ends at.
+    /// * `cost` - returns the cost per unit of flow of an edge, given its source and destination.
+    fn min_cost_max_flow<C>(&'a self, source: &K, sink: &K, cost: C) -> Result<(W, W), GraphError>
+    where
+        C: Fn(&K, &K) -> W
+    {
+        let indices: HashMap<K, usize> = self.node_keys()
+            .enumerate()
+            .map(|(index, key)| (key, index))
+            .collect();
+        let (Some(&source), Some(&sink)) = (indices.get(source), indices.get(sink)) else {
+            return Ok((W::zero(), W::zero()));
+        };
+
+        let mut network = FlowNetwork::new(indices.len());
+        for (key, &index) in &indices {
+            for (edge, capacity) in self.get_weighted_edges(key).into_iter().flatten() {
+                if let Some(&destination) = indices.get(edge) {
+                    network.add_edge(index, destination, *capacity, cost(key, edge));
+                }
+            }
+        }
+        network.min_cost_max_flow(source, sink)
+    }
+}
+
+impl<'a, T, K, V, W> FlowGraph<'a, K, V, W> for T
+where
+    T: WeightedGraph<'a, K, V, W>,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a,
+    W: PartialOrd + Zero + Copy + Sub<Output = W> + Mul<Output = W> + Neg<Output = W> + 'a
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adjacency_list_graph::AdjacencyListGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_min_cost_max_flow() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("source"),
+                String::from("warehouse-1"),
+                String::from("warehouse-2"),
+                String::from("sink"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 2);
+        graph.add_weighted_connection(&0, &2, 1);
+        graph.add_weighted_connection(&1, &2, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&2, &3, 2);
+        let cost = |source: &u16, destination: &u16| match (source, destination) {
+            (0, 1) => 1,
+            (0, 2) => 2,
+            (1, 2) => 1,
+            (1, 3) => 3,
+            _ => 1
+        };
+
+        assert_eq!(graph.min_cost_max_flow(&0, &3, cost), Ok((3, 10)));
+        assert_eq!(graph.min_cost_max_flow(&3, &0, cost), Ok((0, 0)));
+        assert_eq!(graph.min_cost_max_flow(&0, &0, cost), Ok((0, 0)));
+    }
+
+    #[test]
+    fn test_min_cost_max_flow_negative_cycle() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            (1..=4).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &2, 1);
+        graph.add_weighted_connection(&2, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        let cost = |source: &u16, destination: &u16| match (source, destination) {
+            (1, 2) | (2, 1) => -1,
+            _ => 1
+        };
+
+        assert_eq!(graph.min_cost_max_flow(&0, &3, cost), Err(GraphError::NegativeCycle));
+    }
+}
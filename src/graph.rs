@@ -1,3 +1,127 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Write};
+use std::hash::{Hash, Hasher};
+
+use crate::error::GraphError;
+
+/// A 64-bit FNV-1a hasher. Unlike `DefaultHasher`, its algorithm is fixed, and integers are
+/// hashed as little-endian bytes, so its output doesn't change between Rust versions or
+/// platforms.
+struct FnvHasher(u64);
+
+impl FnvHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> FnvHasher {
+        FnvHasher(FnvHasher::OFFSET_BASIS)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(FnvHasher::PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+/// The differences between two states of a graph that share a key space.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphDiff<K> {
+    /// The keys of the nodes only in the newer graph.
+    pub added_nodes: Vec<K>,
+    /// The keys of the nodes only in the older graph.
+    pub removed_nodes: Vec<K>,
+    /// The `(source, destination)` connections only in the newer graph.
+    pub added_edges: Vec<(K, K)>,
+    /// The `(source, destination)` connections only in the older graph.
+    pub removed_edges: Vec<(K, K)>
+}
+
+/// Returns the connections of a graph that another graph doesn't have, counting parallel
+/// connections separately, in key order.
+fn missing_edges<'a, G, K, V>(graph: &'a G, other: &'a G) -> Vec<(K, K)>
+where
+    G: Graph<'a, K, V> + ?Sized,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+    let mut missing: Vec<(K, K)> = vec![];
+    for source in graph.node_keys() {
+        let mut counts: HashMap<K, usize> = HashMap::new();
+        for destination in other.get_edges(&source).into_iter().flatten() {
+            *counts.entry(*destination).or_insert(0) += 1;
+        }
+        for destination in graph.get_edges(&source).into_iter().flatten() {
+            match counts.get_mut(destination) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => missing.push((source, *destination))
+            }
+        }
+    }
+    missing
+}
+
+/// Writes a GML node entry for every node in a graph, labeled with its value.
+pub(crate) fn write_gml_nodes<'a, G, K, V>(graph: &'a G, gml: &mut String)
+where
+    G: Graph<'a, K, V> + ?Sized,
+    K: Copy + Eq + Display + 'a,
+    V: PartialEq + Display + 'a
+{
+    for key in graph.node_keys() {
+        if let Some(value) = graph.get_value(&key) {
+            let label = value.to_string().replace('"', "&quot;");
+            writeln!(gml, "  node [ id {} label \"{}\" ]", key, label).unwrap();
+        }
+    }
+}
+
+/// Quotes a value as a GraphViz DOT string, escaping backslashes and quotes.
+pub(crate) fn dot_quote<T: Display>(value: &T) -> String {
+    format!("\"{}\"", value.to_string().replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Writes a DOT node statement for every node in a graph, in the given key order, labeled with
+/// its value.
+pub(crate) fn write_dot_nodes<'a, G, K, V>(graph: &'a G, keys: &[K], dot: &mut String)
+where
+    G: Graph<'a, K, V> + ?Sized,
+    K: Copy + Eq + Display + 'a,
+    V: PartialEq + Display + 'a
+{
+    for key in keys {
+        if let Some(value) = graph.get_value(key) {
+            writeln!(dot, "  {} [label={}];", key, dot_quote(value)).unwrap();
+        }
+    }
+}
+
 pub trait Graph<'a, K, V>
 where
     K: Copy + Eq + 'a,
@@ -6,17 +130,33 @@ where
     /// An iterator that iterates over the edges of a node in the graph.
     type EdgeIterator: DoubleEndedIterator<Item = &'a K>;
 
-    /// Inserts a node into the graph, returning the key it was inserted by.
+    /// An iterator that iterates over the keys of every node in the graph.
+    type NodeKeyIterator: Iterator<Item = K>;
+
+    /// Inserts a node into the graph, returning the key it was inserted by, or
+    /// `GraphError::CapacityExceeded` if the key type can't represent the key of a new node. The
+    /// graph is left unchanged on error.
     /// # Arguments
     /// * `node` - the node to insert into the graph.
-    fn insert(&mut self, node: V) -> K;
+    fn try_insert(&mut self, node: V) -> Result<K, GraphError>;
 
-    /// Removes a node from the graph, returning the value if it was found, `None` otherwise.
+    /// Inserts a node into the graph, returning the key it was inserted by. Panics if the key
+    /// type can't represent the key of a new node; use `try_insert` to handle that case.
+    /// # Arguments
+    /// * `node` - the node to insert into the graph.
+    fn insert(&mut self, node: V) -> K {
+        self.try_insert(node).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Removes a node from the graph along with every connection into or out of it, returning
+    /// the value if it was found, `None` otherwise. The keys of the other nodes in the graph stay
+    /// valid.
     /// # Arguments
     /// * `key` - the key of the node to remove from the graph.
     fn remove(&mut self, key: &K) -> Option<V>;
 
-    /// Adds a connection between two nodes in the graph.
+    /// Adds a connection between two nodes in the graph. Returns `false` if either key doesn't
+    /// refer to a node in the graph.
     /// # Arguments
     /// * `source` - the key of the source node for the connection.
     /// * `destination` - the key of the destination node for the connection.
@@ -28,10 +168,20 @@ where
     /// * `destination` - the key of the destination node for the connection.
     fn remove_connection(&mut self, source: &K, destination: &K) -> bool;
 
+    /// Removes every connection into a node in the graph, returning the number of connections removed.
+    /// # Arguments
+    /// * `key` - the key of the destination node for the connections.
+    fn remove_incoming(&mut self, key: &K) -> usize;
+
+    /// Removes every connection out of a node in the graph, returning the number of connections removed.
+    /// # Arguments
+    /// * `key` - the key of the source node for the connections.
+    fn remove_outgoing(&mut self, key: &K) -> usize;
+
     /// Returns the node and its edges in the graph, given its key, if it exists, otherwise `None`.
     /// # Arguments
     /// * `key` - the key to return the node and edges for
-    fn get(&'a self, key: &K) -> Option<(&V, Self::EdgeIterator)>;
+    fn get(&'a self, key: &K) -> Option<(&'a V, Self::EdgeIterator)>;
 
     /// Returns the value of a node in the graph, given its key, if it exists, otherwise `None`.
     /// # Arguments
@@ -42,4 +192,328 @@ where
     /// # Arguments
     /// * `key` - the key of the node to return the edges for
     fn get_edges(&'a self, key: &K) -> Option<Self::EdgeIterator>;
+
+    /// Returns the number of nodes in the graph.
+    fn node_count(&'a self) -> usize {
+        self.node_keys().count()
+    }
+
+    /// Returns the number of connections in the graph, counting parallel connections separately.
+    fn edge_count(&'a self) -> usize {
+        self.node_keys()
+            .map(|key| self.get_edges(&key).map_or(0, |edges| edges.count()))
+            .sum()
+    }
+
+    /// Returns the edges of a node in the graph, given its key, or `GraphError::InvalidKey` if it
+    /// doesn't exist.
+    /// # Arguments
+    /// * `key` - the key of the node to return the edges for
+    fn try_get_edges(&'a self, key: &K) -> Result<Self::EdgeIterator, GraphError> {
+        self.get_edges(key).ok_or(GraphError::InvalidKey)
+    }
+
+    /// Returns an iterator over the keys of every node in the graph, in key order. Keys of
+    /// removed nodes are skipped.
+    fn node_keys(&'a self) -> Self::NodeKeyIterator;
+
+    /// Returns whether a key refers to a node in the graph.
+    /// # Arguments
+    /// * `key` - the key of the node to look for.
+    fn contains_node(&self, key: &K) -> bool {
+        self.get_value(key).is_some()
+    }
+
+    /// Returns whether there is at least one connection from one node to another in the graph.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    fn has_edge(&'a self, source: &K, destination: &K) -> bool {
+        self.get_edges(source).is_some_and(|mut edges| edges.any(|edge| edge == destination))
+    }
+
+    /// Returns the number of parallel connections from one node to another in the graph.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connections.
+    /// * `destination` - the key of the destination node for the connections.
+    fn connection_count(&'a self, source: &K, destination: &K) -> usize {
+        self.get_edges(source)
+            .map_or(0, |edges| edges.filter(|edge| *edge == destination).count())
+    }
+
+    /// Returns the keys of every node with a connection into a node in the graph, in key order.
+    /// This scans the connections of every node in the graph.
+    /// # Arguments
+    /// * `key` - the key of the node to return the predecessors for
+    fn predecessors(&'a self, key: &K) -> Vec<K> {
+        self.node_keys()
+            .filter(|source| self.has_edge(source, key))
+            .collect()
+    }
+
+    /// Returns the number of connections out of a node in the graph, or `None` if the node
+    /// doesn't exist.
+    /// # Arguments
+    /// * `key` - the key of the node to return the out-degree for
+    fn out_degree(&'a self, key: &K) -> Option<usize> {
+        Some(self.get_edges(key)?.count())
+    }
+
+    /// Returns the number of connections into a node in the graph, or `None` if the node doesn't
+    /// exist. This scans the connections of every node in the graph, so it takes O(E) time.
+    /// # Arguments
+    /// * `key` - the key of the node to return the in-degree for
+    fn in_degree(&'a self, key: &K) -> Option<usize> {
+        self.get_value(key)?;
+        Some(self.node_keys().map(|source| self.connection_count(&source, key)).sum())
+    }
+
+    /// Returns the number of connections into or out of a node in the graph, i.e. the sum of its
+    /// in-degree and out-degree, or `None` if the node doesn't exist. Like `in_degree`, this
+    /// scans the connections of every node in the graph.
+    /// # Arguments
+    /// * `key` - the key of the node to return the degree for
+    fn degree(&'a self, key: &K) -> Option<usize> {
+        Some(self.out_degree(key)? + self.in_degree(key)?)
+    }
+
+    /// Returns the degree of a node in the undirected view of the graph, where connections in
+    /// opposite directions between two nodes pair up into a single undirected edge. Towards each
+    /// other node, this counts the larger of the number of connections out to it and in from it,
+    /// so a mutual `a -> b` and `b -> a` pair counts once, unlike in `degree`. Every self-loop
+    /// counts twice. This scans the connections of every node in the graph. Returns `None` if the
+    /// node doesn't exist.
+    /// # Arguments
+    /// * `key` - the key of the node to return the degree for
+    fn undirected_degree(&'a self, key: &K) -> Option<usize>
+    where
+        K: Hash
+    {
+        let mut outgoing: HashMap<K, usize> = HashMap::new();
+        for destination in self.get_edges(key)? {
+            *outgoing.entry(*destination).or_insert(0) += 1;
+        }
+        let loops = outgoing.remove(key).unwrap_or(0);
+
+        let mut degree = 2 * loops;
+        for source in self.node_keys().filter(|source| source != key) {
+            let incoming = self.connection_count(&source, key);
+            degree += incoming.max(outgoing.remove(&source).unwrap_or(0));
+        }
+        Some(degree)
+    }
+
+    /// Returns an iterator over the keys of every node connected to a node in the graph in either
+    /// direction, yielding its successors first and then its predecessors, without duplicates.
+    /// # Arguments
+    /// * `key` - the key of the node to return the neighbors for
+    fn incident_edges(&'a self, key: &K) -> impl Iterator<Item = K>
+    where
+        K: Hash
+    {
+        let mut seen: HashSet<K> = HashSet::new();
+        self.get_edges(key)
+            .into_iter()
+            .flatten()
+            .copied()
+            .chain(self.predecessors(key))
+            .filter(move |neighbor| seen.insert(*neighbor))
+    }
+
+    /// Returns whether a path is valid in the graph, i.e. every key in it is a node in the graph
+    /// and every consecutive pair of keys is connected. An empty path is trivially valid, as is a
+    /// single-node path whose key is a node in the graph.
+    /// # Arguments
+    /// * `path` - the keys of the nodes along the path, in order.
+    fn is_valid_path(&'a self, path: &[K]) -> bool {
+        path.iter().all(|key| self.get_value(key).is_some())
+            && path.windows(2).all(|pair| self.has_edge(&pair[0], &pair[1]))
+    }
+
+    /// Returns an iterator over every node in the graph, in key order, paired with the
+    /// iterator over its edges.
+    fn adjacency(&'a self) -> impl Iterator<Item = (K, Self::EdgeIterator)> {
+        self.node_keys()
+            .filter_map(move |key| self.get_edges(&key).map(|edges| (key, edges)))
+    }
+
+    /// Returns an iterator over every connection in the graph as `(source, destination)`, with
+    /// sources in key order.
+    fn all_edges(&'a self) -> impl Iterator<Item = (K, &'a K)> {
+        self.adjacency()
+            .flat_map(|(source, edges)| edges.map(move |destination| (source, destination)))
+    }
+
+    /// Returns whether any node in the graph has a connection to itself.
+    fn has_self_loops(&'a self) -> bool {
+        self.node_keys()
+            .any(|key| self.get_edges(&key).is_some_and(|mut edges| edges.any(|e| e == &key)))
+    }
+
+    /// Returns whether any node in the graph has more than one connection to the same node.
+    fn has_parallel_edges(&'a self) -> bool
+    where
+        K: Hash
+    {
+        self.node_keys().any(|key| {
+            let mut destinations: HashSet<&K> = HashSet::new();
+            self.get_edges(&key).is_some_and(|mut edges| edges.any(|e| !destinations.insert(e)))
+        })
+    }
+
+    /// Returns whether the graph is simple, i.e. it has neither self-loops nor parallel edges.
+    fn is_simple(&'a self) -> bool
+    where
+        K: Hash
+    {
+        !self.has_self_loops() && !self.has_parallel_edges()
+    }
+
+    /// Returns the keys of every node in the graph, sorted by a projection of their values.
+    /// Nodes with equal projections stay in key order.
+    /// # Arguments
+    /// * `f` - returns the value to sort a node by, given the node's value.
+    fn nodes_sorted_by<F, O>(&'a self, f: F) -> Vec<K>
+    where
+        F: Fn(&V) -> O,
+        O: Ord
+    {
+        let mut keyed: Vec<(O, K)> = self.node_keys()
+            .filter_map(|key| self.get_value(&key).map(|value| (f(value), key)))
+            .collect();
+        keyed.sort_by(|a, b| a.0.cmp(&b.0));
+        keyed.into_iter().map(|(_, key)| key).collect()
+    }
+
+    /// Returns groups of keys whose nodes have equal values, in key order. Only groups of two or
+    /// more nodes are returned. Since values are only compared with `PartialEq`, this runs in
+    /// quadratic time in the number of nodes.
+    fn duplicate_value_groups(&'a self) -> Vec<Vec<K>> {
+        let keys: Vec<K> = self.node_keys().collect();
+        let mut grouped = vec![false; keys.len()];
+        let mut groups: Vec<Vec<K>> = vec![];
+
+        for (index, key) in keys.iter().enumerate() {
+            if grouped[index] {
+                continue;
+            }
+            let Some(value) = self.get_value(key) else {
+                continue;
+            };
+            let mut group = vec![*key];
+            for (other_index, other) in keys.iter().enumerate().skip(index + 1) {
+                if !grouped[other_index] && self.get_value(other) == Some(value) {
+                    grouped[other_index] = true;
+                    group.push(*other);
+                }
+            }
+            if group.len() > 1 {
+                groups.push(group);
+            }
+        }
+
+        groups
+    }
+
+    /// Returns a hash of the graph's nodes, their values and their connections. Connections are
+    /// sorted per node before hashing, so graphs with equal nodes and connections share a
+    /// fingerprint regardless of the order the connections were added in. The hash is 64-bit
+    /// FNV-1a, so fingerprints are stable between runs, platforms and Rust versions as long as
+    /// the keys and values hash the same way.
+    fn fingerprint(&'a self) -> u64
+    where
+        K: Hash + Ord,
+        V: Hash
+    {
+        let mut hasher = FnvHasher::new();
+        for key in self.node_keys() {
+            key.hash(&mut hasher);
+            self.get_value(&key).hash(&mut hasher);
+            let mut edges: Vec<&K> = self.get_edges(&key).into_iter().flatten().collect();
+            edges.sort();
+            edges.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Returns the destination keys of every node's connections, for each node in key order.
+    fn to_adjacency_vec(&'a self) -> Vec<Vec<K>> {
+        self.adjacency().map(|(_, edges)| edges.copied().collect()).collect()
+    }
+
+    /// Returns the nodes and connections that were added and removed going from this graph to
+    /// another one, where both graphs use the same keys for the same nodes.
+    /// # Arguments
+    /// * `other` - the newer state of the graph.
+    fn diff(&'a self, other: &'a Self) -> GraphDiff<K>
+    where
+        K: Hash
+    {
+        GraphDiff {
+            added_nodes: other.node_keys().filter(|key| self.get_value(key).is_none()).collect(),
+            removed_nodes: self.node_keys().filter(|key| other.get_value(key).is_none()).collect(),
+            added_edges: missing_edges(other, self),
+            removed_edges: missing_edges(self, other)
+        }
+    }
+
+    /// Returns the adjacency matrix of the graph, where the cell at `[i][j]` is `true` if there
+    /// is a connection from the `i`-th node to the `j`-th node, counting nodes in key order.
+    fn to_bool_matrix(&'a self) -> Vec<Vec<bool>>
+    where
+        K: Hash
+    {
+        let indices: HashMap<K, usize> = self.node_keys()
+            .enumerate()
+            .map(|(index, key)| (key, index))
+            .collect();
+        let mut matrix = vec![vec![false; indices.len()]; indices.len()];
+        for (source, &i) in &indices {
+            for destination in self.get_edges(source).into_iter().flatten() {
+                if let Some(&j) = indices.get(destination) {
+                    matrix[i][j] = true;
+                }
+            }
+        }
+        matrix
+    }
+
+    /// Returns the graph in GML format, labeling each node with its value.
+    fn to_gml(&'a self) -> String
+    where
+        K: Display,
+        V: Display
+    {
+        let mut gml = String::from("graph [\n  directed 1\n");
+        write_gml_nodes(self, &mut gml);
+        for source in self.node_keys() {
+            for destination in self.get_edges(&source).into_iter().flatten() {
+                writeln!(gml, "  edge [ source {} target {} ]", source, destination).unwrap();
+            }
+        }
+        gml.push_str("]\n");
+        gml
+    }
+
+    /// Returns the graph as a GraphViz `digraph`, labeling each node with its value. Nodes and
+    /// their connections are written in ascending key order, so the output is stable.
+    fn to_dot(&'a self) -> String
+    where
+        K: Display + Ord,
+        V: Display
+    {
+        let mut keys: Vec<K> = self.node_keys().collect();
+        keys.sort();
+        let mut dot = String::from("digraph {\n");
+        write_dot_nodes(self, &keys, &mut dot);
+        for source in &keys {
+            let mut destinations: Vec<&K> = self.get_edges(source).into_iter().flatten().collect();
+            destinations.sort();
+            for destination in destinations {
+                writeln!(dot, "  {} -> {};", source, destination).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
 }
@@ -0,0 +1,137 @@
+use num::traits::{PrimInt, Zero};
+
+use crate::adjacency_list_graph::AdjacencyListGraph;
+use crate::error::GraphError;
+use crate::weighted_graph::{NoWeight, WeightedGraph};
+
+/// Collects nodes and connections and builds them into an `AdjacencyListGraph`, checking every
+/// connection only once the graph is built.
+#[derive(Debug)]
+pub struct GraphBuilder<K, V, W = NoWeight>
+where
+    K: PrimInt + Copy,
+    V: PartialEq,
+    W: PartialOrd + Zero + Copy
+{
+    nodes: Vec<V>,
+    edges: Vec<(K, K, W)>
+}
+
+impl<K, V, W> GraphBuilder<K, V, W>
+where
+    K: PrimInt + Copy,
+    V: PartialEq,
+    W: PartialOrd + Zero + Copy
+{
+    pub fn new() -> GraphBuilder<K, V, W> {
+        GraphBuilder {
+            nodes: vec![],
+            edges: vec![]
+        }
+    }
+
+    /// Adds a node to the graph, returning the key it will have in the built graph. Panics if
+    /// the key type can't represent the key of a new node; use `try_add_node` to handle that case.
+    /// # Arguments
+    /// * `value` - the value of the node.
+    pub fn add_node(&mut self, value: V) -> K {
+        self.try_add_node(value).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Adds a node to the graph, returning the key it will have in the built graph, or
+    /// `GraphError::CapacityExceeded` if the key type can't represent the key of a new node.
+    /// # Arguments
+    /// * `value` - the value of the node.
+    pub fn try_add_node(&mut self, value: V) -> Result<K, GraphError> {
+        let key = K::from(self.nodes.len()).ok_or(GraphError::CapacityExceeded)?;
+        self.nodes.push(value);
+        Ok(key)
+    }
+
+    /// Adds a connection between two nodes to the graph.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    pub fn connect(&mut self, source: &K, destination: &K) -> &mut GraphBuilder<K, V, W> {
+        self.connect_weighted(source, destination, W::zero())
+    }
+
+    /// Adds a connection between two nodes to the graph with a weight.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    /// * `weight` - the weight of the connection.
+    pub fn connect_weighted(
+        &mut self,
+        source: &K,
+        destination: &K,
+        weight: W
+    ) -> &mut GraphBuilder<K, V, W> {
+        self.edges.push((*source, *destination, weight));
+        self
+    }
+
+    /// Builds the graph, or returns `GraphError::InvalidKey` if a connection refers to a node
+    /// that was never added.
+    pub fn build(self) -> Result<AdjacencyListGraph<K, V, W>, GraphError> {
+        let mut graph = AdjacencyListGraph::new(self.nodes);
+        for (source, destination, weight) in self.edges {
+            if !graph.add_weighted_connection(&source, &destination, weight) {
+                return Err(GraphError::InvalidKey);
+            }
+        }
+        Ok(graph)
+    }
+}
+
+impl<K, V, W> Default for GraphBuilder<K, V, W>
+where
+    K: PrimInt + Copy,
+    V: PartialEq,
+    W: PartialOrd + Zero + Copy
+{
+    fn default() -> Self {
+        GraphBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::Graph;
+
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let mut builder: GraphBuilder<u16, String, i32> = GraphBuilder::new();
+        let a = builder.add_node(String::from("node-1"));
+        let b = builder.add_node(String::from("node-2"));
+        let c = builder.add_node(String::from("node-3"));
+        builder.connect_weighted(&a, &b, 4).connect_weighted(&b, &c, 2).connect(&c, &a);
+
+        let graph = builder.build().unwrap();
+        assert_eq!(graph.get_value(&c), Some(&String::from("node-3")));
+        assert!(graph.get_weighted_edges(&a).unwrap().eq(vec![(&b, &4)]));
+        assert!(graph.get_weighted_edges(&b).unwrap().eq(vec![(&c, &2)]));
+        assert!(graph.get_weighted_edges(&c).unwrap().eq(vec![(&a, &0)]));
+    }
+
+    #[test]
+    fn test_build_dangling() {
+        let mut builder: GraphBuilder<u16, String> = GraphBuilder::new();
+        let a = builder.add_node(String::from("node-1"));
+        builder.connect(&a, &a).connect(&a, &5);
+
+        assert!(matches!(builder.build(), Err(GraphError::InvalidKey)));
+    }
+
+    #[test]
+    fn test_try_add_node_capacity() {
+        let mut builder: GraphBuilder<u8, usize> = GraphBuilder::new();
+        for value in 0..256 {
+            builder.add_node(value);
+        }
+        assert_eq!(builder.try_add_node(256), Err(GraphError::CapacityExceeded));
+        assert_eq!(builder.build().unwrap().node_count(), 256);
+    }
+}
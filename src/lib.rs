@@ -1,4 +1,12 @@
+pub mod acyclic_graph;
 pub mod adjacency_list_graph;
+pub mod adjacency_matrix_graph;
+pub mod analyzable_graph;
+pub mod connected_graph;
+pub mod edge_list_graph;
+pub mod error;
+pub mod flow_graph;
 pub mod graph;
+pub mod graph_builder;
 pub mod weighted_graph;
 pub mod searchable_graph;
@@ -1,22 +1,24 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
+use std::rc::Rc;
 
 use crate::graph::Graph;
 
 #[derive(Debug, Clone)]
-struct LinkedNode<T: Copy> {
-    value: T,
-    parent: Option<Box<LinkedNode<T>>>,
+pub(crate) struct LinkedNode<T: Copy> {
+    pub(crate) value: T,
+    // Shared, so that cloning a node to use as a parent doesn't copy the whole path behind it.
+    pub(crate) parent: Option<Rc<LinkedNode<T>>>,
 }
 
 impl<T> LinkedNode<T>
 where T: Copy
 {
-    fn new(value: T) -> LinkedNode<T> {
+    pub(crate) fn new(value: T) -> LinkedNode<T> {
         LinkedNode { value, parent: None }
     }
 
-    fn flatten(&self) -> Vec<T> {
+    pub(crate) fn flatten(&self) -> Vec<T> {
         let mut list: Vec<T> = vec![];
         let mut current: Option<&LinkedNode<T>> = Some(self);
         while let Some(node) = current {
@@ -31,6 +33,99 @@ where T: Copy
     }
 }
 
+impl<T> Drop for LinkedNode<T>
+where T: Copy
+{
+    // Unlinks the path one node at a time, since dropping a long chain of parents recursively
+    // would overflow the stack.
+    fn drop(&mut self) {
+        let mut parent = self.parent.take();
+        while let Some(node) = parent {
+            parent = match Rc::try_unwrap(node) {
+                Ok(mut node) => node.parent.take(),
+                Err(_) => None
+            };
+        }
+    }
+}
+
+/// Builds the transpose of the graph's connections, mapping every node to the nodes with a
+/// connection into it.
+pub(crate) fn reverse_adjacency<'a, G, K, V>(graph: &'a G) -> HashMap<K, Vec<K>>
+where
+    G: Graph<'a, K, V> + ?Sized,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a
+{
+    let mut reverse: HashMap<K, Vec<K>> = HashMap::new();
+    for source in graph.node_keys() {
+        for destination in graph.get_edges(&source).into_iter().flatten() {
+            reverse.entry(*destination).or_default().push(source);
+        }
+    }
+    reverse
+}
+
+/// Does a depth-first search from a source node to a destination node, visiting the neighbors of
+/// each node in the order `neighbors` returns them. Returns the path found, if any, along with
+/// every node the search visited, in visiting order.
+fn depth_first_trace<K, N, I>(
+    source: &K,
+    destination: &K,
+    mut neighbors: N
+) -> (Option<Vec<K>>, Vec<K>)
+where
+    K: Copy + Hash + Eq,
+    N: FnMut(&K) -> Option<I>,
+    I: DoubleEndedIterator<Item = K>
+{
+    let mut visited: HashSet<K> = HashSet::new();
+    let mut order: Vec<K> = vec![];
+    let mut stack: Vec<LinkedNode<K>> = vec![];
+    stack.push(LinkedNode::new(*source));
+
+    while let Some(node) = stack.pop() {
+        if visited.insert(node.value) {
+            order.push(node.value);
+            if &node.value == destination {
+                return (Some(node.flatten()), order);
+            } else if let Some(edges) = neighbors(&node.value) {
+                for edge in edges.rev() {
+                    let mut edge_node = LinkedNode::new(edge);
+                    edge_node.parent = Some(Rc::new(node.clone()));
+                    stack.push(edge_node);
+                }
+            }
+        }
+    }
+
+    (None, order)
+}
+
+/// Reusable buffers for graph searches, so that many searches in a row don't have to allocate
+/// fresh collections. The buffers are cleared at the start of every search that uses them.
+#[derive(Debug, Clone)]
+pub struct SearchScratch<K: Copy> {
+    visited: HashSet<K>,
+    queue: VecDeque<LinkedNode<K>>,
+}
+
+impl<K> SearchScratch<K>
+where K: Copy
+{
+    pub fn new() -> SearchScratch<K> {
+        SearchScratch { visited: HashSet::new(), queue: VecDeque::new() }
+    }
+}
+
+impl<K> Default for SearchScratch<K>
+where K: Copy
+{
+    fn default() -> Self {
+        SearchScratch::new()
+    }
+}
+
 pub trait SearchableGraph<'a, K, V>: Graph<'a, K, V>
 where
     K: Copy + Hash + Eq + 'a,
@@ -42,26 +137,34 @@ where
     /// * `source` - the key of the source node for the connection.
     /// * `destination` - the key of the destination node for the connection.
     fn find_path_dfs(&'a self, source: &K, destination: &K) -> Option<Vec<K>> {
-        let mut visited: Vec<K> = vec![];
-        let mut stack: Vec<LinkedNode<K>> = vec![];
-        stack.push(LinkedNode::new(source.clone()));
+        self.find_path_dfs_trace(source, destination).0
+    }
 
-        while let Some(node) = stack.pop() {
-            if !visited.contains(&node.value) {
-                visited.push(node.value);
-                if &node.value == destination {
-                    return Some(node.flatten());
-                } else if let Some(edges) = self.get_edges(&node.value) {
-                    for edge in edges.rev() {
-                        let mut edge_node = LinkedNode::new(edge.clone());
-                        edge_node.parent = Some(Box::new(node.clone()));
-                        stack.push(edge_node);
-                    }
-                }
-            }
-        }
+    /// Returns the first path found between two nodes in the graph,
+    /// doing a depth-first search that visits the neighbors of each node in ascending key order,
+    /// so the result doesn't depend on the order connections were added in.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    fn find_path_dfs_sorted(&'a self, source: &K, destination: &K) -> Option<Vec<K>>
+    where
+        K: Ord
+    {
+        let sorted_edges = |key: &K| {
+            let mut edges: Vec<K> = self.get_edges(key)?.copied().collect();
+            edges.sort();
+            Some(edges.into_iter())
+        };
+        depth_first_trace(source, destination, sorted_edges).0
+    }
 
-        None
+    /// Returns the first path found between two nodes in the graph,
+    /// doing a depth-first search, along with every node the search visited, in visiting order.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    fn find_path_dfs_trace(&'a self, source: &K, destination: &K) -> (Option<Vec<K>>, Vec<K>) {
+        depth_first_trace(source, destination, |key| Some(self.get_edges(key)?.copied()))
     }
 
     /// Returns the first path found between two nodes in the graph,
@@ -70,9 +173,25 @@ where
     /// * `source` - the key of the source node for the connection.
     /// * `destination` - the key of the destination node for the connection.
     fn find_path_bfs(&'a self, source: &K, destination: &K) -> Option<Vec<K>> {
-        let mut visited: HashSet<K> = HashSet::new();
-        let mut queue: VecDeque<LinkedNode<K>> = VecDeque::new();
-        queue.push_front(LinkedNode::new(source.clone()));
+        self.find_path_bfs_with(source, destination, &mut SearchScratch::new())
+    }
+
+    /// Returns the first path found between two nodes in the graph,
+    /// doing a breadth-first search that reuses the buffers in `scratch`.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    /// * `scratch` - the buffers to use for the search.
+    fn find_path_bfs_with(
+        &'a self,
+        source: &K,
+        destination: &K,
+        scratch: &mut SearchScratch<K>
+    ) -> Option<Vec<K>> {
+        let SearchScratch { visited, queue } = scratch;
+        visited.clear();
+        queue.clear();
+        queue.push_front(LinkedNode::new(*source));
 
         while let Some(node) = queue.pop_front() {
             if &node.value == destination {
@@ -80,9 +199,9 @@ where
             } else if let Some(edges) = self.get_edges(&node.value) {
                 for edge in edges {
                     if !visited.contains(edge) {
-                        visited.insert(edge.clone());
-                        let mut edge_node = LinkedNode::new(edge.clone());
-                        edge_node.parent = Some(Box::new(node.clone()));
+                        visited.insert(*edge);
+                        let mut edge_node = LinkedNode::new(*edge);
+                        edge_node.parent = Some(Rc::new(node.clone()));
                         queue.push_back(edge_node);
                     }
                 }
@@ -91,6 +210,413 @@ where
 
         None
     }
+
+    /// Returns the first path found between two nodes in the graph by a breadth-first search,
+    /// pairing each node along it with its hop distance from the source. Since a breadth-first
+    /// path is a shortest path, the distances count up from zero along the path.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    fn find_path_bfs_with_depths(&'a self, source: &K, destination: &K) -> Option<Vec<(K, usize)>> {
+        let path = self.find_path_bfs(source, destination)?;
+        Some(path.into_iter().enumerate().map(|(depth, key)| (key, depth)).collect())
+    }
+
+    /// Returns the shortest path between two nodes in the graph whose consecutive edges only
+    /// switch between categories in allowed ways, doing a breadth-first search over pairs of
+    /// nodes and the category of the edge used to reach them. The first edge of a path can have
+    /// any category, and edges with a category outside of `allowed_transitions` are never used.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    /// * `category` - returns the category of an edge, given its source and destination keys.
+    /// * `allowed_transitions` - whether an edge of category `j` may follow an edge of category
+    ///   `i`, at `allowed_transitions[i][j]`.
+    fn find_path_alternating<C, const N: usize>(
+        &'a self,
+        source: &K,
+        destination: &K,
+        category: C,
+        allowed_transitions: &[[bool; N]]
+    ) -> Option<Vec<K>>
+    where
+        C: Fn(&K, &K) -> u8
+    {
+        let mut visited: HashSet<(K, Option<u8>)> = HashSet::new();
+        let mut queue: VecDeque<LinkedNode<(K, Option<u8>)>> = VecDeque::new();
+        visited.insert((*source, None));
+        queue.push_back(LinkedNode::new((*source, None)));
+
+        while let Some(node) = queue.pop_front() {
+            let (key, previous) = node.value;
+            if &key == destination {
+                return Some(node.flatten().into_iter().map(|(key, _)| key).collect());
+            }
+            for edge in self.get_edges(&key).into_iter().flatten() {
+                let next = category(&key, edge);
+                let allowed = (next as usize) < N && match previous {
+                    Some(previous) => allowed_transitions
+                        .get(previous as usize)
+                        .is_some_and(|row| row[next as usize]),
+                    None => (next as usize) < allowed_transitions.len()
+                };
+                if allowed && visited.insert((*edge, Some(next))) {
+                    let mut edge_node = LinkedNode::new((*edge, Some(next)));
+                    edge_node.parent = Some(Rc::new(node.clone()));
+                    queue.push_back(edge_node);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns whether there's a walk of exactly `length` edges between two nodes in the graph,
+    /// where nodes and edges may be repeated, by tracking the set of nodes reachable in each
+    /// number of steps.
+    /// # Arguments
+    /// * `source` - the key of the source node for the walk.
+    /// * `destination` - the key of the destination node for the walk.
+    /// * `length` - the number of edges in the walk.
+    fn has_walk_of_length(&'a self, source: &K, destination: &K, length: usize) -> bool {
+        if self.get_value(source).is_none() {
+            return false;
+        }
+        let mut reached: HashSet<K> = HashSet::from([*source]);
+        for _ in 0..length {
+            reached = reached.iter()
+                .flat_map(|key| self.get_edges(key).into_iter().flatten())
+                .copied()
+                .collect();
+            if reached.is_empty() {
+                return false;
+            }
+        }
+        reached.contains(destination)
+    }
+
+    /// Returns the first path found between two nodes in the graph by a beam search, i.e. a
+    /// breadth-first search that only keeps the `beam_width` best-scoring nodes of each level.
+    /// Since the rest of each level is discarded, this may miss the shortest path, or fail to
+    /// find a path at all even when one exists.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    /// * `beam_width` - the number of nodes to keep in each level.
+    /// * `score` - returns the score of a node, given its key, where higher is better.
+    fn find_path_beam<S>(
+        &'a self,
+        source: &K,
+        destination: &K,
+        beam_width: usize,
+        score: S
+    ) -> Option<Vec<K>>
+    where
+        S: Fn(&K) -> i64
+    {
+        let mut visited: HashSet<K> = HashSet::from([*source]);
+        let mut level: Vec<LinkedNode<K>> = vec![LinkedNode::new(*source)];
+
+        while !level.is_empty() {
+            if let Some(node) = level.iter().find(|node| &node.value == destination) {
+                return Some(node.flatten());
+            }
+            let mut next_level: Vec<LinkedNode<K>> = vec![];
+            for node in &level {
+                for edge in self.get_edges(&node.value).into_iter().flatten() {
+                    if visited.insert(*edge) {
+                        let mut edge_node = LinkedNode::new(*edge);
+                        edge_node.parent = Some(Rc::new(node.clone()));
+                        next_level.push(edge_node);
+                    }
+                }
+            }
+            next_level.sort_by_key(|node| std::cmp::Reverse(score(&node.value)));
+            next_level.truncate(beam_width);
+            level = next_level;
+        }
+
+        None
+    }
+
+    /// Returns every node reachable from a node in the graph, excluding the node itself.
+    /// # Arguments
+    /// * `key` - the key of the node to start from.
+    fn descendants(&'a self, key: &K) -> HashSet<K> {
+        let mut reached: HashSet<K> = HashSet::new();
+        let mut stack: Vec<K> = vec![*key];
+        while let Some(node) = stack.pop() {
+            for edge in self.get_edges(&node).into_iter().flatten() {
+                if reached.insert(*edge) {
+                    stack.push(*edge);
+                }
+            }
+        }
+        reached.remove(key);
+        reached
+    }
+
+    /// Returns every node that can reach a node in the graph, excluding the node itself.
+    /// # Arguments
+    /// * `key` - the key of the node to end at.
+    fn ancestors(&'a self, key: &K) -> HashSet<K> {
+        let reverse = reverse_adjacency(self);
+        let mut reached: HashSet<K> = HashSet::new();
+        let mut stack: Vec<K> = vec![*key];
+        while let Some(node) = stack.pop() {
+            for predecessor in reverse.get(&node).into_iter().flatten() {
+                if reached.insert(*predecessor) {
+                    stack.push(*predecessor);
+                }
+            }
+        }
+        reached.remove(key);
+        reached
+    }
+
+    /// Returns whether removing a node would separate two other nodes in the graph, i.e. whether
+    /// there is a path from `a` to `b`, but every such path passes through `removed`. Returns
+    /// `false` if `removed` is `a` or `b` itself.
+    /// # Arguments
+    /// * `removed` - the key of the node to leave out.
+    /// * `a` - the key of the node to start from.
+    /// * `b` - the key of the node to reach.
+    fn separates(&'a self, removed: &K, a: &K, b: &K) -> bool {
+        if removed == a || removed == b {
+            return false;
+        }
+        let reaches = |skipped: Option<&K>| {
+            let mut visited: HashSet<K> = HashSet::from([*a]);
+            let mut queue: VecDeque<K> = VecDeque::from([*a]);
+            while let Some(node) = queue.pop_front() {
+                if &node == b {
+                    return true;
+                }
+                for edge in self.get_edges(&node).into_iter().flatten() {
+                    if Some(edge) != skipped && visited.insert(*edge) {
+                        queue.push_back(*edge);
+                    }
+                }
+            }
+            false
+        };
+        reaches(None) && !reaches(Some(removed))
+    }
+
+    /// Returns an iterator that lazily walks the nodes that can reach a node in the graph, in
+    /// breadth-first order by their distance to it, excluding the node itself. The predecessors of
+    /// each node are only looked up once the walk reaches it, so nothing is scanned up front.
+    /// # Arguments
+    /// * `key` - the key of the node to end at.
+    fn ancestors_iter(&'a self, key: &K) -> impl Iterator<Item = K> {
+        let mut visited: HashSet<K> = HashSet::from([*key]);
+        let mut queue: VecDeque<K> = VecDeque::from([*key]);
+        let mut pending: VecDeque<K> = VecDeque::new();
+        std::iter::from_fn(move || {
+            while pending.is_empty() {
+                let node = queue.pop_front()?;
+                for predecessor in self.predecessors(&node) {
+                    if visited.insert(predecessor) {
+                        pending.push_back(predecessor);
+                        queue.push_back(predecessor);
+                    }
+                }
+            }
+            pending.pop_front()
+        })
+    }
+
+    /// Returns up to `k` of the nodes closest to a node in the graph, excluding the node itself,
+    /// paired with their hop distance from it, in the order a breadth-first search discovers them.
+    /// # Arguments
+    /// * `source` - the key of the node to start from.
+    /// * `k` - the maximum number of nodes to return.
+    fn k_nearest_by_hops(&'a self, source: &K, k: usize) -> Vec<(K, usize)> {
+        let mut nearest: Vec<(K, usize)> = vec![];
+        let mut visited: HashSet<K> = HashSet::from([*source]);
+        let mut queue: VecDeque<(K, usize)> = VecDeque::from([(*source, 0)]);
+
+        while let Some((node, distance)) = queue.pop_front() {
+            for edge in self.get_edges(&node).into_iter().flatten() {
+                if nearest.len() == k {
+                    return nearest;
+                }
+                if visited.insert(*edge) {
+                    nearest.push((*edge, distance + 1));
+                    queue.push_back((*edge, distance + 1));
+                }
+            }
+        }
+
+        nearest
+    }
+
+    /// Returns the number of nodes within `radius` hops of a node in the graph, excluding the node
+    /// itself, using a breadth-first search that stops at the radius.
+    /// # Arguments
+    /// * `key` - the key of the node to start from.
+    /// * `radius` - the maximum number of hops to count nodes within.
+    fn reach_centrality(&'a self, key: &K, radius: usize) -> usize {
+        let mut visited: HashSet<K> = HashSet::from([*key]);
+        let mut queue: VecDeque<(K, usize)> = VecDeque::from([(*key, 0)]);
+        while let Some((node, distance)) = queue.pop_front() {
+            if distance == radius {
+                continue;
+            }
+            for edge in self.get_edges(&node).into_iter().flatten() {
+                if visited.insert(*edge) {
+                    queue.push_back((*edge, distance + 1));
+                }
+            }
+        }
+        visited.len() - 1
+    }
+
+    /// Returns the number of ordered pairs of distinct nodes `(u, v)` in the graph where `v` is
+    /// reachable from `u`, running a search from every node.
+    fn reachable_pair_count(&'a self) -> usize {
+        self.node_keys().map(|key| self.descendants(&key).len()).sum()
+    }
+
+    /// Returns the eccentricity of every node in the graph, i.e. the number of hops to the node
+    /// farthest away from it, running a breadth-first search from every node. Only nodes
+    /// reachable from a node count towards its eccentricity, so a node without any outgoing
+    /// connections has an eccentricity of zero.
+    fn all_eccentricities(&'a self) -> HashMap<K, usize> {
+        self.node_keys()
+            .map(|source| {
+                let mut visited: HashSet<K> = HashSet::from([source]);
+                let mut queue: VecDeque<(K, usize)> = VecDeque::from([(source, 0)]);
+                let mut eccentricity = 0;
+                while let Some((node, distance)) = queue.pop_front() {
+                    eccentricity = distance;
+                    for edge in self.get_edges(&node).into_iter().flatten() {
+                        if visited.insert(*edge) {
+                            queue.push_back((*edge, distance + 1));
+                        }
+                    }
+                }
+                (source, eccentricity)
+            })
+            .collect()
+    }
+
+    /// Returns the immediate dominator of every node reachable from an entry node in the graph,
+    /// i.e. the closest node that every path from the entry to it must pass through, using the
+    /// iterative algorithm of Cooper, Harvey and Kennedy. The entry node itself has no immediate
+    /// dominator, so it isn't in the map.
+    /// # Arguments
+    /// * `entry` - the key of the node every path starts from.
+    fn immediate_dominators(&'a self, entry: &K) -> HashMap<K, K> {
+        let mut postorder: Vec<K> = vec![];
+        let mut visited: HashSet<K> = HashSet::from([*entry]);
+        let mut stack: Vec<(K, Self::EdgeIterator)> = self.get_edges(entry)
+            .map(|edges| (*entry, edges))
+            .into_iter()
+            .collect();
+        while let Some((node, edges)) = stack.last_mut() {
+            let node = *node;
+            match edges.next() {
+                Some(next) if !visited.contains(next) => {
+                    if let Some(next_edges) = self.get_edges(next) {
+                        visited.insert(*next);
+                        stack.push((*next, next_edges));
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    postorder.push(node);
+                    stack.pop();
+                }
+            }
+        }
+
+        let order: HashMap<K, usize> = postorder.iter()
+            .enumerate()
+            .map(|(index, key)| (*key, index))
+            .collect();
+        let reverse = reverse_adjacency(self);
+        let mut dominators: HashMap<K, K> = HashMap::new();
+        if !order.is_empty() {
+            dominators.insert(*entry, *entry);
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for node in postorder.iter().rev().skip(1) {
+                let mut processed = reverse.get(node)
+                    .into_iter()
+                    .flatten()
+                    .filter(|predecessor| dominators.contains_key(predecessor));
+                let Some(mut dominator) = processed.next().copied() else {
+                    continue;
+                };
+                for predecessor in processed {
+                    let mut other = *predecessor;
+                    while dominator != other {
+                        while order[&dominator] < order[&other] {
+                            dominator = dominators[&dominator];
+                        }
+                        while order[&other] < order[&dominator] {
+                            other = dominators[&other];
+                        }
+                    }
+                }
+                if dominators.get(node) != Some(&dominator) {
+                    dominators.insert(*node, dominator);
+                    changed = true;
+                }
+            }
+        }
+
+        dominators.remove(entry);
+        dominators
+    }
+
+    /// Returns the dominance frontier of every node reachable from an entry node in the graph,
+    /// i.e. the nodes where its dominance ends, using the algorithm of Cytron et al. over the
+    /// dominator tree.
+    /// # Arguments
+    /// * `entry` - the key of the node every path starts from.
+    fn dominance_frontiers(&'a self, entry: &K) -> HashMap<K, HashSet<K>> {
+        let dominators = self.immediate_dominators(entry);
+        let mut children: HashMap<K, Vec<K>> = HashMap::new();
+        for (node, dominator) in &dominators {
+            children.entry(*dominator).or_default().push(*node);
+        }
+
+        // Visit the dominator tree bottom-up, so every child's frontier is known before its parent's.
+        let mut order: Vec<K> = vec![];
+        if self.get_value(entry).is_some() {
+            let mut stack: Vec<K> = vec![*entry];
+            while let Some(node) = stack.pop() {
+                order.push(node);
+                stack.extend(children.get(&node).into_iter().flatten());
+            }
+        }
+
+        let mut frontiers: HashMap<K, HashSet<K>> = HashMap::new();
+        for node in order.into_iter().rev() {
+            let mut frontier: HashSet<K> = self.get_edges(&node)
+                .into_iter()
+                .flatten()
+                .filter(|edge| dominators.get(edge) != Some(&node))
+                .filter(|edge| *edge == entry || dominators.contains_key(edge))
+                .copied()
+                .collect();
+            for child in children.get(&node).into_iter().flatten() {
+                for edge in &frontiers[child] {
+                    if dominators.get(edge) != Some(&node) {
+                        frontier.insert(*edge);
+                    }
+                }
+            }
+            frontiers.insert(node, frontier);
+        }
+
+        frontiers
+    }
 }
 
 impl<'a, T, K, V> SearchableGraph<'a, K, V> for T
@@ -101,3 +627,402 @@ where
 {
 }
 
+#[cfg(test)]
+mod tests {
+    use crate::adjacency_list_graph::AdjacencyListGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_find_path_bfs_with_depths() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+
+        let path = graph.find_path_bfs_with_depths(&0, &4).unwrap();
+        assert_eq!(path, vec![(0, 0), (1, 1), (3, 2), (4, 3)]);
+        assert!(path.windows(2).all(|pair| pair[1].1 == pair[0].1 + 1));
+
+        assert_eq!(graph.find_path_bfs_with_depths(&4, &0), None);
+    }
+
+    #[test]
+    fn test_find_path_bfs_with_scratch() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        let mut scratch = SearchScratch::new();
+
+        let first = graph.find_path_bfs_with(&0, &4, &mut scratch);
+        assert_eq!(first, graph.find_path_bfs(&0, &4));
+        assert_eq!(first, Some(vec![0, 1, 3, 4]));
+
+        let second = graph.find_path_bfs_with(&2, &4, &mut scratch);
+        assert_eq!(second, graph.find_path_bfs(&2, &4));
+        assert_eq!(second, Some(vec![2, 3, 4]));
+
+        assert_eq!(graph.find_path_bfs_with(&4, &0, &mut scratch), None);
+    }
+
+    #[test]
+    fn test_find_path_dfs_trace() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&1, &4);
+
+        let (path, visited) = graph.find_path_dfs_trace(&0, &4);
+        assert_eq!(path, Some(vec![0, 1, 3, 4]));
+        assert_eq!(visited, vec![0, 1, 3, 4]);
+
+        let (path, visited) = graph.find_path_dfs_trace(&2, &0);
+        assert_eq!(path, None);
+        assert_eq!(visited, vec![2, 3, 4]);
+
+        let (path, visited) = graph.find_path_dfs_trace(&0, &2);
+        assert_eq!(path, Some(vec![0, 2]));
+        assert_eq!(visited, vec![0, 1, 3, 4, 2]);
+    }
+
+    #[test]
+    fn test_find_path_alternating() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&3, &2);
+        let category = |source: &u16, destination: &u16| match (source, destination) {
+            (1, 3) => 1,
+            _ => 0
+        };
+        let alternating = [[false, true], [true, false]];
+
+        assert_eq!(graph.find_path_bfs(&0, &2), Some(vec![0, 1, 2]));
+        assert_eq!(
+            graph.find_path_alternating(&0, &2, category, &alternating),
+            Some(vec![0, 1, 3, 2])
+        );
+        assert_eq!(graph.find_path_alternating(&1, &2, category, &alternating), Some(vec![1, 2]));
+
+        let same_category = [[true, false], [false, true]];
+        assert_eq!(
+            graph.find_path_alternating(&0, &2, category, &same_category),
+            Some(vec![0, 1, 2])
+        );
+        assert_eq!(graph.find_path_alternating(&0, &3, category, &same_category), None);
+    }
+
+    #[test]
+    fn test_has_walk_of_length() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &3);
+
+        assert!(graph.has_walk_of_length(&0, &0, 0));
+        assert!(graph.has_walk_of_length(&0, &3, 2));
+        assert!(!graph.has_walk_of_length(&0, &3, 3));
+        assert!(graph.has_walk_of_length(&0, &3, 4));
+        assert!(!graph.has_walk_of_length(&0, &4, 2));
+        assert!(!graph.has_walk_of_length(&4, &0, 5));
+    }
+
+    #[test]
+    fn test_find_path_beam() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&2, &3);
+        let score = |key: &u16| if *key == 1 { 10 } else { 0 };
+
+        assert_eq!(graph.find_path_beam(&0, &3, 2, score), Some(vec![0, 2, 3]));
+        assert_eq!(graph.find_path_beam(&0, &3, 1, score), None);
+        assert_eq!(graph.find_path_beam(&0, &3, 1, |key| i64::from(*key)), Some(vec![0, 2, 3]));
+    }
+
+    #[test]
+    fn test_descendants_and_ancestors() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+
+        let descendants = graph.descendants(&2);
+        let ancestors = graph.ancestors(&2);
+        assert_eq!(descendants, HashSet::from([3, 4]));
+        assert_eq!(ancestors, HashSet::from([0, 1]));
+        assert!(descendants.is_disjoint(&ancestors));
+
+        graph.add_connection(&4, &2);
+        assert_eq!(graph.descendants(&2), HashSet::from([3, 4]));
+        assert_eq!(graph.ancestors(&2), HashSet::from([0, 1, 3, 4]));
+    }
+
+    #[test]
+    fn test_k_nearest_by_hops() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+
+        assert_eq!(graph.k_nearest_by_hops(&0, 2), vec![(1, 1), (2, 1)]);
+        assert_eq!(graph.k_nearest_by_hops(&0, 3), vec![(1, 1), (2, 1), (3, 2)]);
+        assert_eq!(graph.k_nearest_by_hops(&0, 10).len(), 4);
+        assert!(graph.k_nearest_by_hops(&0, 0).is_empty());
+        assert!(graph.k_nearest_by_hops(&4, 2).is_empty());
+    }
+
+    #[test]
+    fn test_find_path_dfs_sorted() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_connection(&0, &2);
+        graph.add_connection(&0, &1);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&1, &3);
+
+        assert_eq!(graph.find_path_dfs(&0, &3), Some(vec![0, 2, 3]));
+        assert_eq!(graph.find_path_dfs_sorted(&0, &3), Some(vec![0, 1, 3]));
+        assert_eq!(graph.find_path_dfs_sorted(&3, &0), None);
+    }
+
+    #[test]
+    fn test_dominance_frontiers() {
+        // An entry block leading into a loop whose body is a diamond, followed by an exit block.
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("entry"),
+                String::from("header"),
+                String::from("then"),
+                String::from("else"),
+                String::from("join"),
+                String::from("exit"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&1, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &4);
+        graph.add_connection(&3, &4);
+        graph.add_connection(&4, &1);
+        graph.add_connection(&4, &5);
+
+        assert_eq!(
+            graph.immediate_dominators(&0),
+            HashMap::from([(1, 0), (2, 1), (3, 1), (4, 1), (5, 4)])
+        );
+        assert_eq!(
+            graph.dominance_frontiers(&0),
+            HashMap::from([
+                (0, HashSet::new()),
+                (1, HashSet::from([1])),
+                (2, HashSet::from([4])),
+                (3, HashSet::from([4])),
+                (4, HashSet::from([1])),
+                (5, HashSet::new()),
+            ])
+        );
+
+        // Nodes that aren't reachable from the entry have no frontier.
+        assert_eq!(graph.dominance_frontiers(&5), HashMap::from([(5, HashSet::new())]));
+        assert!(graph.dominance_frontiers(&9).is_empty());
+    }
+
+    #[test]
+    fn test_separates() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        assert!(graph.separates(&3, &0, &4));
+        assert!(!graph.separates(&1, &0, &3));
+        assert!(!graph.separates(&3, &4, &0));
+        assert!(!graph.separates(&3, &3, &4));
+
+        graph.add_connection(&2, &4);
+        assert!(!graph.separates(&3, &0, &4));
+    }
+
+    #[test]
+    fn test_find_path_dfs_long_line() {
+        let size: u32 = 10_000;
+        let graph: AdjacencyListGraph<u32, u32> = AdjacencyListGraph::path((0..size).collect());
+
+        let path = graph.find_path_dfs(&0, &(size - 1)).unwrap();
+        assert_eq!(path.len(), size as usize);
+        assert!(path.iter().copied().eq(0..size));
+    }
+
+    #[test]
+    fn test_all_eccentricities() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::path(
+            (1..=5).map(|index| format!("node-{}", index)).collect()
+        );
+        assert_eq!(
+            graph.all_eccentricities(),
+            HashMap::from([(0, 4), (1, 3), (2, 2), (3, 1), (4, 0)])
+        );
+
+        for key in 1..5 {
+            graph.add_connection(&key, &(key - 1));
+        }
+        assert_eq!(
+            graph.all_eccentricities(),
+            HashMap::from([(0, 4), (1, 3), (2, 2), (3, 3), (4, 4)])
+        );
+    }
+
+    #[test]
+    fn test_ancestors_iter() {
+        let graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::path(
+            (1..=6).map(|index| format!("node-{}", index)).collect()
+        );
+        assert_eq!(graph.ancestors_iter(&5).take(2).collect::<Vec<_>>(), vec![4, 3]);
+        assert!(graph.ancestors_iter(&5).eq(vec![4, 3, 2, 1, 0]));
+        assert_eq!(graph.ancestors_iter(&0).next(), None);
+
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+        assert!(graph.ancestors_iter(&4).eq(vec![3, 1, 2, 0]));
+    }
+
+    #[test]
+    fn test_reach_centrality() {
+        let mut graph: AdjacencyListGraph<u16, String> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_connection(&0, &1);
+        graph.add_connection(&0, &2);
+        graph.add_connection(&1, &3);
+        graph.add_connection(&2, &3);
+        graph.add_connection(&3, &4);
+
+        assert_eq!(graph.reach_centrality(&0, 0), 0);
+        assert_eq!(graph.reach_centrality(&0, 1), 2);
+        assert_eq!(graph.reach_centrality(&0, 2), 3);
+        assert_eq!(graph.reach_centrality(&0, 10), 4);
+        assert_eq!(graph.reach_centrality(&4, 3), 0);
+    }
+
+    #[test]
+    fn test_reachable_pair_count() {
+        let mut chain: AdjacencyListGraph<u16, String> = AdjacencyListGraph::path(
+            (1..=4).map(|index| format!("node-{}", index)).collect()
+        );
+        assert_eq!(chain.reachable_pair_count(), 6);
+
+        chain.add_connection(&3, &0);
+        assert_eq!(chain.reachable_pair_count(), 12);
+    }
+}
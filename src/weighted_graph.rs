@@ -1,10 +1,22 @@
-use num::traits::Zero;
-use std::cmp::Ordering;
+use num::traits::{ToPrimitive, Zero};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt::{Display, Write};
+use std::hash::Hash;
 use std::ops::Add;
+use std::rc::Rc;
 
-use crate::graph::Graph;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::acyclic_graph::AcyclicGraph;
+use crate::analyzable_graph::undirected_adjacency;
+use crate::error::GraphError;
+use crate::graph::{dot_quote, write_dot_nodes, write_gml_nodes, Graph};
+use crate::searchable_graph::LinkedNode;
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct NoWeight {}
 
 impl PartialEq for NoWeight {
@@ -12,10 +24,6 @@ impl PartialEq for NoWeight {
     fn eq(&self, _other: &NoWeight) -> bool {
         true
     }
-    #[inline]
-    fn ne(&self, _other: &NoWeight) -> bool {
-        false
-    }
 }
 
 impl PartialOrd for NoWeight {
@@ -44,6 +52,78 @@ impl Zero for NoWeight {
     }
 }
 
+/// An entry in a priority queue, ordered so that a `BinaryHeap` pops the entry with the lowest
+/// cost first. Costs that can't be compared are treated as equal.
+#[derive(Debug, Clone)]
+pub(crate) struct MinScored<W, T> {
+    pub(crate) cost: W,
+    pub(crate) item: T
+}
+
+impl<W: PartialOrd, T> PartialEq for MinScored<W, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<W: PartialOrd, T> Eq for MinScored<W, T> {}
+
+impl<W: PartialOrd, T> PartialOrd for MinScored<W, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: PartialOrd, T> Ord for MinScored<W, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Returns the sum of the weights of a list of `(source, destination, weight)` edges, such as a
+/// spanning tree.
+/// # Arguments
+/// * `edges` - the edges to sum the weights of.
+pub fn total_weight<K, W>(edges: &[(K, K, W)]) -> W
+where
+    W: Zero + Copy
+{
+    edges.iter().fold(W::zero(), |total, (_, _, weight)| total + *weight)
+}
+
+/// A union-find structure over indices, with path halving and union by size.
+struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> DisjointSet {
+        DisjointSet { parents: (0..size).collect(), sizes: vec![1; size] }
+    }
+
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+
+    /// Merges the sets holding two indices, returning `false` if they were already the same set.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        true
+    }
+}
 
 pub trait WeightedGraph<'a, K, V, W>: Graph<'a, K, V> 
 where
@@ -54,7 +134,8 @@ where
     /// An iterator that iterates over the weighted edges of a node in the graph.
     type WeightedEdgeIterator: DoubleEndedIterator<Item = (&'a K, &'a W)>;
 
-    /// Adds a connection between two nodes in the graph with a weight.
+    /// Adds a connection between two nodes in the graph with a weight. Returns `false` if either
+    /// key doesn't refer to a node in the graph.
     /// # Arguments
     /// * `source` - the key of the source node for the connection.
     /// * `destination` - the key of the destination node for the connection.
@@ -66,14 +147,1453 @@ where
         weight: W
     ) -> bool; 
 
+    /// Removes the first connection between two nodes in the graph that has a given weight,
+    /// leaving any parallel connections with other weights in place. Returns `false` if no
+    /// connection matches.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    /// * `weight` - the weight of the connection to remove.
+    fn remove_weighted_connection(&mut self, source: &K, destination: &K, weight: &W) -> bool
+    where
+        W: PartialEq;
+
     /// Gets a node and its weighted edges in the graph, given its key, if it exists, otherwise `None`.
     /// # Arguments
     /// * `key` - the key to return the node and edges for
-    fn get_weighted(&'a self, key: &K) -> Option<(&V, Self::WeightedEdgeIterator)>;
+    fn get_weighted(&'a self, key: &K) -> Option<(&'a V, Self::WeightedEdgeIterator)>;
 
     /// Gets the weighted edges of a node in the graph, given its key, if it exists, otherwise `None`.
     /// # Arguments
     /// * `key` - the key of the node to return the edges for
     fn get_weighted_edges(&'a self, key: &K) -> Option<Self::WeightedEdgeIterator>;
+
+    /// Returns the weight of the connection from one node to another in the graph, if it exists.
+    /// If there are parallel connections, the weight of the first one is returned.
+    /// # Arguments
+    /// * `source` - the key of the source node for the connection.
+    /// * `destination` - the key of the destination node for the connection.
+    fn get_weight(&'a self, source: &K, destination: &K) -> Option<&'a W> {
+        self.get_weighted_edges(source)?
+            .find(|(edge, _)| *edge == destination)
+            .map(|(_, weight)| weight)
+    }
+
+    /// Returns an iterator over every connection in the graph as `(source, destination, weight)`,
+    /// with sources in key order.
+    fn all_weighted_edges(&'a self) -> impl Iterator<Item = (K, &'a K, &'a W)> {
+        self.node_keys().flat_map(move |source| {
+            self.get_weighted_edges(&source)
+                .into_iter()
+                .flatten()
+                .map(move |(destination, weight)| (source, destination, weight))
+        })
+    }
+
+    /// Returns the cheapest path between two nodes in the graph along with its cost, where the
+    /// cost of a path is the sum of its edge weights plus the cost of every node along it,
+    /// including the source and the destination.
+    /// # Arguments
+    /// * `source` - the key of the source node for the path.
+    /// * `destination` - the key of the destination node for the path.
+    /// * `node_cost` - returns the cost of passing through a node, given its key.
+    fn find_path_with_node_costs<NC>(
+        &'a self,
+        source: &K,
+        destination: &K,
+        node_cost: NC
+    ) -> Option<(Vec<K>, W)>
+    where
+        K: Hash,
+        W: PartialOrd + Zero + Copy,
+        NC: Fn(&K) -> W
+    {
+        let mut settled: HashSet<K> = HashSet::new();
+        let mut frontier: BinaryHeap<MinScored<W, LinkedNode<K>>> = BinaryHeap::new();
+        frontier.push(MinScored { cost: node_cost(source), item: LinkedNode::new(*source) });
+
+        while let Some(MinScored { cost, item: node }) = frontier.pop() {
+            if !settled.insert(node.value) {
+                continue;
+            }
+            if &node.value == destination {
+                return Some((node.flatten(), cost));
+            } else if let Some(edges) = self.get_weighted_edges(&node.value) {
+                for (edge, weight) in edges {
+                    if !settled.contains(edge) {
+                        let mut edge_node = LinkedNode::new(*edge);
+                        edge_node.parent = Some(Rc::new(node.clone()));
+                        frontier.push(MinScored {
+                            cost: cost + *weight + node_cost(edge),
+                            item: edge_node
+                        });
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the cheapest path between two nodes in the graph along with its cost, doing an A*
+    /// search that expands nodes in order of their cost so far plus the heuristic's estimate of
+    /// their remaining cost. A heuristic that always returns zero makes this equivalent to
+    /// Dijkstra's algorithm. A node is expanded again whenever a cheaper path to it is found, so
+    /// the returned path is the cheapest as long as the heuristic never overestimates the
+    /// remaining cost, i.e. it is admissible, even if it isn't consistent. A consistent heuristic
+    /// expands every node at most once. If the heuristic isn't admissible, the returned path may
+    /// not be the cheapest.
+    /// # Arguments
+    /// * `source` - the key of the source node for the path.
+    /// * `destination` - the key of the destination node for the path.
+    /// * `heuristic` - estimates the cost of reaching the destination from a node, given its key.
+    fn find_path_astar<H>(&'a self, source: &K, destination: &K, heuristic: H) -> Option<(Vec<K>, W)>
+    where
+        K: Hash,
+        W: PartialOrd + Zero + Copy,
+        H: Fn(&K) -> W
+    {
+        // The cheapest cost found so far to every node reached.
+        let mut costs: HashMap<K, W> = HashMap::from([(*source, W::zero())]);
+        let mut frontier: BinaryHeap<MinScored<W, (W, LinkedNode<K>)>> = BinaryHeap::new();
+        frontier.push(MinScored {
+            cost: heuristic(source),
+            item: (W::zero(), LinkedNode::new(*source))
+        });
+
+        while let Some(MinScored { item: (cost, node), .. }) = frontier.pop() {
+            if costs.get(&node.value).is_some_and(|best| *best < cost) {
+                continue;
+            }
+            if &node.value == destination {
+                return Some((node.flatten(), cost));
+            }
+            for (edge, weight) in self.get_weighted_edges(&node.value).into_iter().flatten() {
+                let edge_cost = cost + *weight;
+                if costs.get(edge).is_none_or(|best| edge_cost < *best) {
+                    costs.insert(*edge, edge_cost);
+                    let mut edge_node = LinkedNode::new(*edge);
+                    edge_node.parent = Some(Rc::new(node.clone()));
+                    frontier.push(MinScored {
+                        cost: edge_cost + heuristic(edge),
+                        item: (edge_cost, edge_node)
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the cheapest path from any of several source nodes to any of several destination
+    /// nodes in the graph along with its cost, doing a Dijkstra search from every source at once
+    /// that stops as soon as a destination is reached.
+    /// # Arguments
+    /// * `sources` - the keys of the nodes the path may start from.
+    /// * `destinations` - the keys of the nodes the path may end at.
+    fn find_path_multi(&'a self, sources: &[K], destinations: &[K]) -> Option<(Vec<K>, W)>
+    where
+        K: Hash,
+        W: PartialOrd + Zero + Copy
+    {
+        let destinations: HashSet<&K> = destinations.iter().collect();
+        let mut settled: HashSet<K> = HashSet::new();
+        let mut frontier: BinaryHeap<MinScored<W, LinkedNode<K>>> = sources.iter()
+            .filter(|source| self.get_value(source).is_some())
+            .map(|source| MinScored { cost: W::zero(), item: LinkedNode::new(*source) })
+            .collect();
+
+        while let Some(MinScored { cost, item: node }) = frontier.pop() {
+            if !settled.insert(node.value) {
+                continue;
+            }
+            if destinations.contains(&node.value) {
+                return Some((node.flatten(), cost));
+            }
+            for (edge, weight) in self.get_weighted_edges(&node.value).into_iter().flatten() {
+                if !settled.contains(edge) {
+                    let mut edge_node = LinkedNode::new(*edge);
+                    edge_node.parent = Some(Rc::new(node.clone()));
+                    frontier.push(MinScored { cost: cost + *weight, item: edge_node });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns an approximate travelling salesman tour of the graph along with its cost, using
+    /// the nearest-neighbor heuristic: starting from a node, always follow the cheapest
+    /// connection to a node not yet visited, then return to the start. The tour is closed, so it
+    /// begins and ends with the start node and visits every other node exactly once, except in a
+    /// graph with a single node, whose tour is just that node at no cost. This is a heuristic, so
+    /// the tour isn't necessarily the cheapest one. Returns `None` if the walk gets stuck before
+    /// visiting every node, or can't return to the start.
+    /// # Arguments
+    /// * `start` - the key of the node the tour starts and ends at.
+    fn approx_tsp_tour(&'a self, start: &K) -> Option<(Vec<K>, W)>
+    where
+        K: Hash,
+        W: PartialOrd + Zero + Copy
+    {
+        self.get_value(start)?;
+        let count = self.node_keys().count();
+        if count == 1 {
+            return Some((vec![*start], W::zero()));
+        }
+        let mut visited: HashSet<K> = HashSet::from([*start]);
+        let mut tour: Vec<K> = vec![*start];
+        let mut cost = W::zero();
+        let mut current = *start;
+
+        while tour.len() <= count {
+            // Once every node is visited, the only place left to go is back to the start.
+            let returning = tour.len() == count;
+            let (next, weight) = self.get_weighted_edges(&current)?
+                .filter(|(edge, _)| {
+                    if returning { *edge == start } else { !visited.contains(edge) }
+                })
+                .fold(None, |cheapest: Option<(&K, &W)>, (edge, weight)| match cheapest {
+                    None => Some((edge, weight)),
+                    Some((_, lowest)) if weight < lowest => Some((edge, weight)),
+                    _ => cheapest
+                })?;
+            visited.insert(*next);
+            tour.push(*next);
+            cost = cost + *weight;
+            current = *next;
+        }
+
+        Some((tour, cost))
+    }
+
+    /// Returns the cheapest path between two nodes in the graph whose cost is strictly greater
+    /// than the cost of the cheapest path, along with its cost. Every node may be settled twice,
+    /// once for each of its two cheapest distinct costs, so the path may revisit nodes.
+    /// # Arguments
+    /// * `source` - the key of the source node for the path.
+    /// * `destination` - the key of the destination node for the path.
+    fn find_second_shortest_path(&'a self, source: &K, destination: &K) -> Option<(Vec<K>, W)>
+    where
+        K: Hash,
+        W: PartialOrd + Zero + Copy
+    {
+        let mut settled: HashMap<K, Vec<W>> = HashMap::new();
+        let mut frontier: BinaryHeap<MinScored<W, LinkedNode<K>>> = BinaryHeap::new();
+        frontier.push(MinScored { cost: W::zero(), item: LinkedNode::new(*source) });
+
+        while let Some(MinScored { cost, item: node }) = frontier.pop() {
+            let costs = settled.entry(node.value).or_default();
+            if costs.len() == 2 || costs.first().is_some_and(|first| cost <= *first) {
+                continue;
+            }
+            costs.push(cost);
+            if &node.value == destination && costs.len() == 2 {
+                return Some((node.flatten(), cost));
+            }
+            for (edge, weight) in self.get_weighted_edges(&node.value).into_iter().flatten() {
+                let mut edge_node = LinkedNode::new(*edge);
+                edge_node.parent = Some(Rc::new(node.clone()));
+                frontier.push(MinScored { cost: cost + *weight, item: edge_node });
+            }
+        }
+
+        None
+    }
+
+    /// Returns every node reachable from a node in the graph along with the cost of the cheapest
+    /// path to it, sorted by ascending cost. The source node itself comes first, with a cost of zero.
+    /// # Arguments
+    /// * `source` - the key of the node to start from.
+    fn reachable_sorted_by_cost(&'a self, source: &K) -> Vec<(K, W)>
+    where
+        K: Hash,
+        W: PartialOrd + Zero + Copy
+    {
+        let mut reachable: Vec<(K, W)> = vec![];
+        if self.get_value(source).is_none() {
+            return reachable;
+        }
+
+        let mut settled: HashSet<K> = HashSet::new();
+        let mut frontier: BinaryHeap<MinScored<W, K>> = BinaryHeap::new();
+        frontier.push(MinScored { cost: W::zero(), item: *source });
+
+        while let Some(MinScored { cost, item: node }) = frontier.pop() {
+            if !settled.insert(node) {
+                continue;
+            }
+            reachable.push((node, cost));
+            for (edge, weight) in self.get_weighted_edges(&node).into_iter().flatten() {
+                if !settled.contains(edge) {
+                    frontier.push(MinScored { cost: cost + *weight, item: *edge });
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// Returns the cost of the cheapest path from every node that can reach a sink node in the
+    /// graph, found by running Dijkstra's algorithm backwards over the connections. The sink
+    /// itself has a cost of zero.
+    /// # Arguments
+    /// * `sink` - the key of the node every path ends at.
+    fn shortest_distances_to(&'a self, sink: &K) -> HashMap<K, W>
+    where
+        K: Hash,
+        W: PartialOrd + Zero + Copy
+    {
+        let mut distances: HashMap<K, W> = HashMap::new();
+        if self.get_value(sink).is_none() {
+            return distances;
+        }
+        let mut incoming: HashMap<K, Vec<(K, W)>> = HashMap::new();
+        for source in self.node_keys() {
+            for (destination, weight) in self.get_weighted_edges(&source).into_iter().flatten() {
+                incoming.entry(*destination).or_default().push((source, *weight));
+            }
+        }
+
+        let mut frontier: BinaryHeap<MinScored<W, K>> = BinaryHeap::new();
+        frontier.push(MinScored { cost: W::zero(), item: *sink });
+        while let Some(MinScored { cost, item: node }) = frontier.pop() {
+            if distances.contains_key(&node) {
+                continue;
+            }
+            distances.insert(node, cost);
+            for (predecessor, weight) in incoming.get(&node).into_iter().flatten() {
+                if !distances.contains_key(predecessor) {
+                    frontier.push(MinScored { cost: cost + *weight, item: *predecessor });
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Returns the widest path tree from a node in the graph, mapping every other node reachable
+    /// from it to its parent on the widest path and the bottleneck of that path, i.e. the lowest
+    /// edge weight along it. The widest path to a node is the one whose bottleneck is as high as
+    /// possible, found with a variant of Dijkstra's algorithm that always settles the node with the
+    /// highest bottleneck next.
+    /// # Arguments
+    /// * `source` - the key of the node to start from.
+    fn widest_path_tree(&'a self, source: &K) -> HashMap<K, (K, W)>
+    where
+        K: Hash,
+        W: PartialOrd + Copy
+    {
+        let mut tree: HashMap<K, (K, W)> = HashMap::new();
+        let mut settled: HashSet<K> = HashSet::from([*source]);
+        let mut frontier: BinaryHeap<Reverse<MinScored<W, (K, K)>>> = self.get_weighted_edges(source)
+            .into_iter()
+            .flatten()
+            .map(|(edge, weight)| Reverse(MinScored { cost: *weight, item: (*source, *edge) }))
+            .collect();
+
+        while let Some(Reverse(MinScored { cost, item: (parent, node) })) = frontier.pop() {
+            if !settled.insert(node) {
+                continue;
+            }
+            tree.insert(node, (parent, cost));
+            for (edge, weight) in self.get_weighted_edges(&node).into_iter().flatten() {
+                if !settled.contains(edge) {
+                    let bottleneck = if *weight < cost { *weight } else { cost };
+                    frontier.push(Reverse(MinScored { cost: bottleneck, item: (node, *edge) }));
+                }
+            }
+        }
+
+        tree
+    }
+
+    /// Returns the weighted eccentricity of a node in the graph, i.e. the cost of the cheapest
+    /// path to the node farthest away from it, or `None` if some node isn't reachable from it.
+    /// # Arguments
+    /// * `key` - the key of the node to return the eccentricity for.
+    fn weighted_eccentricity(&'a self, key: &K) -> Option<W>
+    where
+        K: Hash,
+        W: PartialOrd + Zero + Copy
+    {
+        let reachable: Vec<(K, W)> = self.reachable_sorted_by_cost(key)
+            .into_iter()
+            .filter(|(node, _)| self.get_value(node).is_some())
+            .collect();
+        if reachable.is_empty() || reachable.len() != self.node_keys().count() {
+            return None;
+        }
+        reachable.last().map(|(_, cost)| *cost)
+    }
+
+    /// Returns the keys of every node whose weighted eccentricity equals the weighted radius of
+    /// the graph, in key order. Returns an empty list if the graph isn't strongly connected, since
+    /// then some node has no eccentricity.
+    fn weighted_center(&'a self) -> Vec<K>
+    where
+        K: Hash,
+        W: PartialOrd + Zero + Copy
+    {
+        let mut eccentricities: Vec<(K, W)> = vec![];
+        for key in self.node_keys() {
+            let Some(eccentricity) = self.weighted_eccentricity(&key) else {
+                return vec![];
+            };
+            eccentricities.push((key, eccentricity));
+        }
+
+        let Some(radius) = eccentricities.iter()
+            .map(|(_, eccentricity)| *eccentricity)
+            .reduce(|radius, eccentricity| if eccentricity < radius { eccentricity } else { radius })
+        else {
+            return vec![];
+        };
+        eccentricities.into_iter()
+            .filter(|(_, eccentricity)| *eccentricity == radius)
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    /// Returns the edges of a minimum spanning forest of the graph using Kruskal's algorithm,
+    /// treating every edge as undirected, with one tree for every connected component. Edges are
+    /// returned in the order they were chosen, and ties between equal weights are broken by
+    /// source key and then destination key, so the result is deterministic. Self-loops are ignored.
+    fn minimum_spanning_tree_kruskal(&'a self) -> Vec<(K, K, W)>
+    where
+        K: Hash + Ord,
+        W: PartialOrd + Copy
+    {
+        let indices: HashMap<K, usize> = self.node_keys()
+            .enumerate()
+            .map(|(index, key)| (key, index))
+            .collect();
+        let mut edges: Vec<(K, K, W)> = vec![];
+        for source in self.node_keys() {
+            for (destination, weight) in self.get_weighted_edges(&source).into_iter().flatten() {
+                if &source != destination && indices.contains_key(destination) {
+                    edges.push((source, *destination, *weight));
+                }
+            }
+        }
+        edges.sort_by(|a, b| {
+            a.2.partial_cmp(&b.2)
+                .unwrap_or(Ordering::Equal)
+                .then(a.0.cmp(&b.0))
+                .then(a.1.cmp(&b.1))
+        });
+
+        let mut components = DisjointSet::new(indices.len());
+        edges.into_iter()
+            .filter(|(source, destination, _)| {
+                components.union(indices[source], indices[destination])
+            })
+            .collect()
+    }
+
+    /// Returns the edges of a minimum spanning tree of the graph using Prim's algorithm, grown
+    /// from a start node and treating every edge as undirected. Only the connected component
+    /// holding the start node is spanned, so for a disconnected graph the other components are
+    /// left out entirely. Each edge is returned as `(parent, child, weight)` in the order it was
+    /// added to the tree. Zero-weight edges are handled like any other edge.
+    /// # Arguments
+    /// * `start` - the key of the node to grow the tree from.
+    fn minimum_spanning_tree_prim(&'a self, start: &K) -> Vec<(K, K, W)>
+    where
+        K: Hash,
+        W: PartialOrd + Copy
+    {
+        let mut tree: Vec<(K, K, W)> = vec![];
+        if self.get_value(start).is_none() {
+            return tree;
+        }
+        let mut incoming: HashMap<K, Vec<(K, W)>> = HashMap::new();
+        for source in self.node_keys() {
+            for (destination, weight) in self.get_weighted_edges(&source).into_iter().flatten() {
+                incoming.entry(*destination).or_default().push((source, *weight));
+            }
+        }
+
+        let mut visited: HashSet<K> = HashSet::from([*start]);
+        let mut frontier: BinaryHeap<MinScored<W, (K, K)>> = BinaryHeap::new();
+        let mut node = *start;
+        loop {
+            let outgoing = self.get_weighted_edges(&node)
+                .into_iter()
+                .flatten()
+                .map(|(edge, weight)| (*edge, *weight));
+            let reverse = incoming.get(&node).into_iter().flatten().copied();
+            for (neighbor, weight) in outgoing.chain(reverse) {
+                if !visited.contains(&neighbor) && self.get_value(&neighbor).is_some() {
+                    frontier.push(MinScored { cost: weight, item: (node, neighbor) });
+                }
+            }
+
+            let next = std::iter::from_fn(|| frontier.pop())
+                .find(|entry| !visited.contains(&entry.item.1));
+            let Some(MinScored { cost, item: (parent, child) }) = next else {
+                break;
+            };
+            visited.insert(child);
+            tree.push((parent, child, cost));
+            node = child;
+        }
+
+        tree
+    }
+
+    /// Returns the cost of the cheapest path from a node to every node reachable from it,
+    /// relaxing edges in topological order, which takes linear time and handles negative weights.
+    /// The source node itself has a cost of zero. Returns `GraphError::Cycle` if the graph isn't
+    /// acyclic.
+    /// # Arguments
+    /// * `source` - the key of the node to start from.
+    fn shortest_distances_dag(&'a self, source: &K) -> Result<HashMap<K, W>, GraphError>
+    where
+        Self: Sized,
+        K: Hash,
+        W: PartialOrd + Zero + Copy
+    {
+        relax_dag(self, source, |candidate, current| candidate < current)
+    }
+
+    /// Returns the cost of the most expensive path from a node to every node reachable from it,
+    /// relaxing edges in topological order. The source node itself has a cost of zero. Returns
+    /// `GraphError::Cycle` if the graph isn't acyclic.
+    /// # Arguments
+    /// * `source` - the key of the node to start from.
+    fn longest_distances_dag(&'a self, source: &K) -> Result<HashMap<K, W>, GraphError>
+    where
+        Self: Sized,
+        K: Hash,
+        W: PartialOrd + Zero + Copy
+    {
+        relax_dag(self, source, |candidate, current| candidate > current)
+    }
+
+    /// Returns the cheapest new connection that would join the connected components of two nodes
+    /// in the graph, treating every edge as undirected, as a `(source, destination, weight)` triple.
+    /// Returns `None` if the nodes are already connected or no candidate connection exists.
+    /// # Arguments
+    /// * `a` - the key of a node in the component the connection starts from.
+    /// * `b` - the key of a node in the component the connection ends at.
+    /// * `candidate_weight` - returns the weight of a potential new connection between two nodes,
+    ///   or `None` if they can't be connected.
+    fn cheapest_connecting_edge<CW>(
+        &'a self,
+        a: &K,
+        b: &K,
+        candidate_weight: CW
+    ) -> Option<(K, K, W)>
+    where
+        K: Hash,
+        W: PartialOrd + Copy,
+        CW: Fn(&K, &K) -> Option<W>
+    {
+        if self.get_value(a).is_none() || self.get_value(b).is_none() {
+            return None;
+        }
+        let adjacency = undirected_adjacency(self);
+        let component = |start: &K| {
+            let mut reached: HashSet<K> = HashSet::from([*start]);
+            let mut stack = vec![*start];
+            while let Some(node) = stack.pop() {
+                for neighbor in adjacency.get(&node).into_iter().flatten() {
+                    if reached.insert(*neighbor) {
+                        stack.push(*neighbor);
+                    }
+                }
+            }
+            reached
+        };
+        let component_a = component(a);
+        if component_a.contains(b) {
+            return None;
+        }
+        let component_b = component(b);
+
+        let mut cheapest: Option<(K, K, W)> = None;
+        for source in self.node_keys().filter(|key| component_a.contains(key)) {
+            for destination in self.node_keys().filter(|key| component_b.contains(key)) {
+                let Some(weight) = candidate_weight(&source, &destination) else {
+                    continue;
+                };
+                if cheapest.is_none_or(|(_, _, current)| weight < current) {
+                    cheapest = Some((source, destination, weight));
+                }
+            }
+        }
+        cheapest
+    }
+
+    /// Returns the smallest mean edge weight of any cycle in the graph, using Karp's algorithm, or
+    /// `None` if the graph is acyclic. Connections whose weight can't be converted to `f64` are
+    /// left out.
+    fn minimum_mean_cycle(&'a self) -> Option<f64>
+    where
+        K: Hash,
+        W: ToPrimitive
+    {
+        let keys: Vec<K> = self.node_keys().collect();
+        let indices: HashMap<K, usize> = keys.iter()
+            .enumerate()
+            .map(|(index, key)| (*key, index))
+            .collect();
+        let mut edges: Vec<(usize, usize, f64)> = vec![];
+        for (source, key) in keys.iter().enumerate() {
+            for (destination, weight) in self.get_weighted_edges(key).into_iter().flatten() {
+                let converted = (indices.get(destination), weight.to_f64());
+                let (Some(&destination), Some(weight)) = converted else {
+                    continue;
+                };
+                edges.push((source, destination, weight));
+            }
+        }
+
+        // distances[k][v] is the weight of the lightest walk of exactly k edges ending at v,
+        // starting anywhere.
+        let n = keys.len();
+        let mut distances: Vec<Vec<Option<f64>>> = vec![vec![Some(0.0); n]];
+        for k in 1..=n {
+            let mut row: Vec<Option<f64>> = vec![None; n];
+            for &(source, destination, weight) in &edges {
+                if let Some(distance) = distances[k - 1][source] {
+                    let candidate = distance + weight;
+                    if row[destination].is_none_or(|current| candidate < current) {
+                        row[destination] = Some(candidate);
+                    }
+                }
+            }
+            distances.push(row);
+        }
+
+        (0..n)
+            .filter_map(|v| {
+                let full = distances[n][v]?;
+                (0..n)
+                    .filter_map(|k| distances[k][v].map(|partial| (full - partial) / (n - k) as f64))
+                    .reduce(f64::max)
+            })
+            .reduce(f64::min)
+    }
+
+    /// Returns the total weight of the connections crossing a cut of the graph, i.e. with exactly
+    /// one endpoint on one side of it, in either direction.
+    /// # Arguments
+    /// * `side_a` - the keys of the nodes on one side of the cut.
+    fn cut_weight(&'a self, side_a: &HashSet<K>) -> W
+    where
+        K: Hash,
+        W: Zero + Copy
+    {
+        let mut total = W::zero();
+        for source in self.node_keys() {
+            let in_a = side_a.contains(&source);
+            for (destination, weight) in self.get_weighted_edges(&source).into_iter().flatten() {
+                if side_a.contains(destination) != in_a {
+                    total = total + *weight;
+                }
+            }
+        }
+        total
+    }
+
+    /// Returns the largest edge weight in the graph, or `None` if the graph has no edges.
+    fn max_weight(&'a self) -> Option<W>
+    where
+        W: PartialOrd + Copy
+    {
+        self.node_keys()
+            .flat_map(|key| self.get_weighted_edges(&key).into_iter().flatten())
+            .fold(None, |max, (_, weight)| match max {
+                Some(max) if *weight <= max => Some(max),
+                _ => Some(*weight)
+            })
+    }
+
+    /// Returns the smallest edge weight in the graph, or `None` if the graph has no edges.
+    fn min_weight(&'a self) -> Option<W>
+    where
+        W: PartialOrd + Copy
+    {
+        self.node_keys()
+            .flat_map(|key| self.get_weighted_edges(&key).into_iter().flatten())
+            .fold(None, |min, (_, weight)| match min {
+                Some(min) if *weight >= min => Some(min),
+                _ => Some(*weight)
+            })
+    }
+
+    /// Returns the Laplacian matrix `D - A` of the graph, treating every edge as undirected,
+    /// where `A` is the weighted adjacency matrix and `D` holds the weighted degree of each node
+    /// on its diagonal. Rows and columns follow the key order of the nodes, and self-loops are
+    /// ignored. Each pair of nodes is joined at most once, with the weight of the first connection
+    /// between them in key order, so an undirected edge stored as a connection in each direction
+    /// isn't counted twice. Connections whose weight can't be converted to `f64` are left out.
+    fn laplacian_matrix(&'a self) -> Vec<Vec<f64>>
+    where
+        K: Hash,
+        W: ToPrimitive
+    {
+        let keys: Vec<K> = self.node_keys().collect();
+        let indices: HashMap<K, usize> = keys.iter()
+            .enumerate()
+            .map(|(index, key)| (*key, index))
+            .collect();
+        let mut laplacian = vec![vec![0.0; indices.len()]; indices.len()];
+        let mut joined: HashSet<(usize, usize)> = HashSet::new();
+
+        for (i, source) in keys.iter().enumerate() {
+            for (destination, weight) in self.get_weighted_edges(source).into_iter().flatten() {
+                let (Some(&j), Some(weight)) = (indices.get(destination), weight.to_f64()) else {
+                    continue;
+                };
+                if i == j || !joined.insert((i.min(j), i.max(j))) {
+                    continue;
+                }
+                laplacian[i][j] -= weight;
+                laplacian[j][i] -= weight;
+                laplacian[i][i] += weight;
+                laplacian[j][j] += weight;
+            }
+        }
+
+        laplacian
+    }
+
+    /// Returns the destination keys and weights of every node's connections, for each node in
+    /// key order.
+    fn to_weighted_adjacency_vec(&'a self) -> Vec<Vec<(K, W)>>
+    where
+        W: Copy
+    {
+        self.node_keys()
+            .map(|key| {
+                self.get_weighted_edges(&key)
+                    .into_iter()
+                    .flatten()
+                    .map(|(destination, weight)| (*destination, *weight))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the graph in GML format, labeling each node with its value and each edge with
+    /// its weight.
+    fn to_weighted_gml(&'a self) -> String
+    where
+        K: Display,
+        V: Display,
+        W: Display
+    {
+        let mut gml = String::from("graph [\n  directed 1\n");
+        write_gml_nodes(self, &mut gml);
+        for source in self.node_keys() {
+            for (destination, weight) in self.get_weighted_edges(&source).into_iter().flatten() {
+                writeln!(
+                    gml,
+                    "  edge [ source {} target {} value {} ]",
+                    source, destination, weight
+                ).unwrap();
+            }
+        }
+        gml.push_str("]\n");
+        gml
+    }
+
+    /// Returns the graph as a GraphViz `digraph`, labeling each node with its value and each
+    /// edge with its weight. Nodes and their connections are written in ascending key order, so
+    /// the output is stable.
+    fn to_weighted_dot(&'a self) -> String
+    where
+        K: Display + Ord,
+        V: Display,
+        W: Display
+    {
+        let mut keys: Vec<K> = self.node_keys().collect();
+        keys.sort();
+        let mut dot = String::from("digraph {\n");
+        write_dot_nodes(self, &keys, &mut dot);
+        for source in &keys {
+            let mut edges: Vec<(&K, &W)> =
+                self.get_weighted_edges(source).into_iter().flatten().collect();
+            edges.sort_by_key(|(destination, _)| *destination);
+            for (destination, weight) in edges {
+                writeln!(dot, "  {} -> {} [label={}];", source, destination, dot_quote(weight))
+                    .unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
 }
 
+/// Relaxes the weighted edges of an acyclic graph in topological order, starting from a source
+/// node with a cost of zero, and keeps a candidate cost whenever `better` prefers it to the
+/// current one. Returns `GraphError::Cycle` if the graph isn't acyclic.
+/// # Arguments
+/// * `graph` - the graph to relax the edges of.
+/// * `source` - the key of the node to start from.
+/// * `better` - returns whether a candidate cost should replace the current cost of a node.
+fn relax_dag<'a, G, K, V, W>(
+    graph: &'a G,
+    source: &K,
+    better: impl Fn(&W, &W) -> bool
+) -> Result<HashMap<K, W>, GraphError>
+where
+    G: WeightedGraph<'a, K, V, W>,
+    K: Copy + Hash + Eq + 'a,
+    V: PartialEq + 'a,
+    W: PartialOrd + Zero + Copy + 'a
+{
+    let layers = graph.topological_layers()?;
+    let mut distances: HashMap<K, W> = HashMap::new();
+    if graph.get_value(source).is_some() {
+        distances.insert(*source, W::zero());
+    }
+
+    for node in layers.iter().flatten() {
+        let Some(&distance) = distances.get(node) else {
+            continue;
+        };
+        for (edge, weight) in graph.get_weighted_edges(node).into_iter().flatten() {
+            let candidate = distance + *weight;
+            if distances.get(edge).is_none_or(|current| better(&candidate, current)) {
+                distances.insert(*edge, candidate);
+            }
+        }
+    }
+
+    Ok(distances)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adjacency_list_graph::AdjacencyListGraph;
+    use crate::analyzable_graph::AnalyzableGraph;
+
+    use super::*;
+
+    #[test]
+    fn test_find_path_with_node_costs() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+
+        let free = graph.find_path_with_node_costs(&0, &3, |_| 0);
+        assert_eq!(free, Some((vec![0, 1, 3], 2)));
+
+        let expensive = graph.find_path_with_node_costs(&0, &3, |key| if *key == 1 { 10 } else { 1 });
+        assert_eq!(expensive, Some((vec![0, 2, 3], 7)));
+
+        assert_eq!(graph.find_path_with_node_costs(&3, &0, |_| 0), None);
+    }
+
+    #[test]
+    fn test_to_weighted_gml() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        let gml = graph.to_weighted_gml();
+
+        assert!(gml.starts_with("graph ["));
+        assert_eq!(gml.matches("node [").count(), 4);
+        assert_eq!(gml.matches("edge [").count(), 4);
+        assert!(gml.contains("node [ id 0 label \"node-1\" ]"));
+        assert!(gml.contains("edge [ source 2 target 3 value 2 ]"));
+    }
+
+    #[test]
+    fn test_to_weighted_dot() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        let dot = graph.to_weighted_dot();
+
+        assert!(dot.starts_with("digraph {\n"));
+        assert!(dot.contains("  0 [label=\"node-1\"];\n"));
+        assert!(dot.contains("  0 -> 1 [label=\"1\"];\n  0 -> 2 [label=\"2\"];\n"));
+        assert_eq!(dot.matches(" -> ").count(), 4);
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_reachable_sorted_by_cost() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        let unreachable = graph.insert(String::from("node-5"));
+        graph.add_weighted_connection(&unreachable, &0, 1);
+
+        let reachable = graph.reachable_sorted_by_cost(&0);
+        assert_eq!(reachable, vec![(0, 0), (1, 1), (2, 2), (3, 2)]);
+        assert!(reachable.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+        assert!(graph.reachable_sorted_by_cost(&9).is_empty());
+    }
+
+    #[test]
+    fn test_max_and_min_weight() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+                String::from("node-6"),
+            ]
+        );
+        assert_eq!(graph.max_weight(), None);
+        assert_eq!(graph.min_weight(), None);
+
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&0, &3, 3);
+        graph.add_weighted_connection(&1, &2, 4);
+        graph.add_weighted_connection(&1, &3, 5);
+        graph.add_weighted_connection(&1, &4, 6);
+        graph.add_weighted_connection(&2, &3, 7);
+        assert_eq!(graph.max_weight(), Some(7));
+        assert_eq!(graph.min_weight(), Some(1));
+    }
+
+    #[test]
+    fn test_laplacian_matrix() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        let laplacian = graph.laplacian_matrix();
+
+        assert_eq!(laplacian.len(), 4);
+        assert_eq!(laplacian[0], vec![3.0, -1.0, -2.0, 0.0]);
+        assert_eq!(laplacian[3], vec![0.0, -1.0, -2.0, 3.0]);
+        for row in &laplacian {
+            assert_eq!(row.iter().sum::<f64>(), 0.0);
+        }
+
+        // Storing each edge in both directions doesn't double its weight.
+        graph.add_weighted_connection(&1, &0, 1);
+        graph.add_weighted_connection(&3, &1, 1);
+        graph.add_weighted_connection(&2, &0, 2);
+        graph.add_weighted_connection(&3, &2, 2);
+        assert_eq!(graph.laplacian_matrix(), laplacian);
+    }
+
+    #[test]
+    fn test_longest_distances_dag() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+                String::from("node-6"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 3);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&1, &3, 4);
+        graph.add_weighted_connection(&2, &3, 6);
+        graph.add_weighted_connection(&3, &4, 1);
+        graph.add_weighted_connection(&1, &4, 2);
+        graph.add_weighted_connection(&5, &0, 1);
+
+        let distances = graph.longest_distances_dag(&0).unwrap();
+        assert_eq!(distances.len(), 5);
+        assert_eq!(distances[&0], 0);
+        assert_eq!(distances[&1], 3);
+        assert_eq!(distances[&3], 8);
+        assert_eq!(distances[&4], 9);
+        assert!(!distances.contains_key(&5));
+
+        graph.add_weighted_connection(&4, &0, 1);
+        assert_eq!(graph.longest_distances_dag(&0), Err(GraphError::Cycle));
+    }
+
+    #[test]
+    fn test_shortest_distances_dag() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            (1..=5).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_weighted_connection(&0, &1, 3);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&1, &3, -4);
+        graph.add_weighted_connection(&2, &3, 1);
+        graph.add_weighted_connection(&4, &0, 1);
+
+        let distances = graph.shortest_distances_dag(&0).unwrap();
+        assert_eq!(distances, HashMap::from([(0, 0), (1, 3), (2, 2), (3, -1)]));
+
+        graph.add_weighted_connection(&3, &0, 1);
+        assert_eq!(graph.shortest_distances_dag(&0), Err(GraphError::Cycle));
+    }
+
+    #[test]
+    fn test_cheapest_connecting_edge() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&2, &1, 1);
+        graph.add_weighted_connection(&3, &4, 1);
+        let positions = [0, 4, 9, 12, 20];
+        let distance = |a: &u16, b: &u16| {
+            let (a, b) = (positions[*a as usize], positions[*b as usize]);
+            (a != 9 && b != 9).then_some(i32::abs(a - b))
+        };
+
+        assert_eq!(graph.cheapest_connecting_edge(&0, &4, distance), Some((1, 3, 8)));
+        assert_eq!(graph.cheapest_connecting_edge(&4, &0, distance), Some((3, 1, 8)));
+        assert_eq!(graph.cheapest_connecting_edge(&0, &2, distance), None);
+    }
+
+    #[test]
+    fn test_minimum_mean_cycle() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+                String::from("node-5"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &2, 2);
+        assert_eq!(graph.minimum_mean_cycle(), None);
+
+        // A cycle with a mean weight of 4, and one with a mean weight of 2.5.
+        graph.add_weighted_connection(&2, &0, 9);
+        graph.add_weighted_connection(&2, &3, 3);
+        graph.add_weighted_connection(&3, &4, 1);
+        graph.add_weighted_connection(&4, &3, 4);
+        let mean = graph.minimum_mean_cycle().unwrap();
+        assert!((mean - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_find_second_shortest_path() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        assert_eq!(graph.find_second_shortest_path(&0, &3), Some((vec![0, 2, 3], 4)));
+
+        graph.add_weighted_connection(&0, &3, 2);
+        assert_eq!(graph.find_second_shortest_path(&0, &3), Some((vec![0, 2, 3], 4)));
+
+        graph.add_weighted_connection(&3, &0, 1);
+        assert_eq!(graph.find_second_shortest_path(&0, &0), Some((vec![0, 3, 0], 3)));
+        assert_eq!(graph.find_path_with_node_costs(&1, &2, |_| 0), Some((vec![1, 3, 0, 2], 4)));
+        assert_eq!(graph.find_second_shortest_path(&1, &2).map(|(_, cost)| cost), Some(7));
+    }
+
+    #[test]
+    fn test_to_weighted_adjacency_vec() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        assert_eq!(
+            graph.to_weighted_adjacency_vec(),
+            vec![vec![(1, 1), (2, 2)], vec![(3, 1)], vec![(3, 2)], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_find_path_astar() {
+        // A 3x3 grid, where moving right costs 1 and moving down costs 2.
+        let mut grid: AdjacencyListGraph<u16, (i32, i32), i32> = AdjacencyListGraph::new(
+            (0..9).map(|index| (index % 3, index / 3)).collect()
+        );
+        for index in 0..9u16 {
+            if index % 3 < 2 {
+                grid.add_weighted_connection(&index, &(index + 1), 1);
+            }
+            if index / 3 < 2 {
+                grid.add_weighted_connection(&index, &(index + 3), 2);
+            }
+        }
+        let manhattan = |key: &u16| {
+            let (x, y) = grid.get_value(key).unwrap();
+            (2 - x) + (2 - y) * 2
+        };
+
+        let (path, cost) = grid.find_path_astar(&0, &8, manhattan).unwrap();
+        assert_eq!(cost, 6);
+        assert!(grid.is_valid_path(&path));
+        assert_eq!(path.first(), Some(&0));
+        assert_eq!(path.last(), Some(&8));
+
+        let dijkstra = grid.find_path_astar(&0, &8, |_| 0);
+        assert_eq!(dijkstra, grid.find_path_with_node_costs(&0, &8, |_| 0));
+        assert_eq!(dijkstra.map(|(_, cost)| cost), Some(6));
+        assert_eq!(grid.find_path_astar(&8, &0, manhattan), None);
+    }
+
+    #[test]
+    fn test_find_path_astar_inconsistent_heuristic() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            (1..=5).map(|index| format!("node-{}", index)).collect()
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&0, &2, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&2, &3, 2);
+        graph.add_weighted_connection(&3, &4, 3);
+
+        // Admissible but not consistent: node 2 reaches node 3 first, along the worse route, so
+        // node 3 has to be expanded again once the better route through node 1 is found.
+        let heuristic = |key: &u16| if *key == 1 { 4 } else { 0 };
+        assert_eq!(graph.find_path_astar(&0, &4, heuristic), Some((vec![0, 1, 3, 4], 5)));
+    }
+
+    #[test]
+    fn test_find_path_multi() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("entrance-1"),
+                String::from("entrance-2"),
+                String::from("hall"),
+                String::from("exit-1"),
+                String::from("exit-2"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &2, 5);
+        graph.add_weighted_connection(&1, &2, 2);
+        graph.add_weighted_connection(&0, &3, 6);
+        graph.add_weighted_connection(&2, &3, 3);
+        graph.add_weighted_connection(&2, &4, 2);
+
+        assert_eq!(graph.find_path_multi(&[0, 1], &[3, 4]), Some((vec![1, 2, 4], 4)));
+        assert_eq!(graph.find_path_multi(&[0], &[3, 4]), Some((vec![0, 3], 6)));
+        assert_eq!(graph.find_path_multi(&[0, 2], &[2]), Some((vec![2], 0)));
+        assert_eq!(graph.find_path_multi(&[3, 4], &[0, 1]), None);
+    }
+
+    #[test]
+    fn test_remove_weighted_connection() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        graph.add_weighted_connection(&0, &1, 5);
+
+        assert!(!graph.remove_weighted_connection(&0, &1, &3));
+        assert!(graph.remove_weighted_connection(&0, &1, &1));
+        assert_eq!(
+            graph.get_weighted_edges(&0).unwrap().collect::<Vec<_>>(),
+            vec![(&2, &2), (&1, &5)]
+        );
+        assert!(!graph.remove_weighted_connection(&0, &1, &1));
+        assert!(!graph.remove_weighted_connection(&9, &1, &5));
+    }
+
+    #[test]
+    fn test_weighted_center() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            (1..=5).map(|index| format!("node-{}", index)).collect()
+        );
+        for (a, b, weight) in [(0, 1, 1), (1, 2, 2), (2, 3, 2), (3, 4, 1)] {
+            graph.add_weighted_connection(&a, &b, weight);
+            graph.add_weighted_connection(&b, &a, weight);
+        }
+
+        assert_eq!(graph.weighted_eccentricity(&0), Some(6));
+        assert_eq!(graph.weighted_eccentricity(&2), Some(3));
+        assert_eq!(graph.weighted_center(), vec![2]);
+
+        graph.insert(String::from("node-6"));
+        assert_eq!(graph.weighted_eccentricity(&2), None);
+        assert!(graph.weighted_center().is_empty());
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree_kruskal() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        graph.add_weighted_connection(&3, &0, 2);
+        graph.insert(String::from("node-5"));
+        graph.insert(String::from("node-6"));
+        graph.add_weighted_connection(&5, &4, 7);
+
+        let tree = graph.minimum_spanning_tree_kruskal();
+        assert_eq!(tree, vec![(0, 1, 1), (1, 3, 1), (0, 2, 2), (5, 4, 7)]);
+        assert_eq!(total_weight(&tree), 11);
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree_prim() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        graph.add_weighted_connection(&3, &2, 0);
+        graph.insert(String::from("node-5"));
+
+        let tree = graph.minimum_spanning_tree_prim(&3);
+        assert_eq!(tree, vec![(3, 2, 0), (3, 1, 1), (1, 0, 1)]);
+        assert_eq!(total_weight(&tree), 2);
+        assert_eq!(
+            total_weight(&graph.minimum_spanning_tree_prim(&0)),
+            total_weight(&graph.minimum_spanning_tree_kruskal())
+        );
+        assert!(graph.minimum_spanning_tree_prim(&4).is_empty());
+        assert!(graph.minimum_spanning_tree_prim(&9).is_empty());
+    }
+
+    #[test]
+    fn test_widest_path_tree() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        graph.insert(String::from("node-5"));
+        graph.insert(String::from("node-6"));
+        graph.add_weighted_connection(&3, &4, 5);
+        graph.add_weighted_connection(&0, &5, 9);
+        graph.add_weighted_connection(&5, &4, 3);
+
+        let tree = graph.widest_path_tree(&0);
+        assert_eq!(
+            tree,
+            HashMap::from([
+                (1, (0, 1)),
+                (2, (0, 2)),
+                (3, (2, 2)),
+                (5, (0, 9)),
+                (4, (5, 3)),
+            ])
+        );
+        assert!(graph.widest_path_tree(&4).is_empty());
+    }
+
+    #[test]
+    fn test_shortest_distances_to() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        graph.insert(String::from("node-5"));
+        graph.add_weighted_connection(&4, &2, 1);
+        graph.add_weighted_connection(&0, &3, 5);
+
+        let distances = graph.shortest_distances_to(&3);
+        assert_eq!(distances, HashMap::from([(3, 0), (1, 1), (2, 2), (0, 2), (4, 3)]));
+        for (node, distance) in &distances {
+            let forward = graph.reachable_sorted_by_cost(node);
+            assert!(forward.contains(&(3, *distance)));
+        }
+        assert_eq!(graph.shortest_distances_to(&4), HashMap::from([(4, 0)]));
+    }
+
+    #[test]
+    fn test_cut_weight() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        let side_a = HashSet::from([0, 1]);
+
+        assert_eq!(graph.cut_size(&side_a), 2);
+        assert_eq!(graph.cut_weight(&side_a), 3);
+        assert_eq!(graph.cut_weight(&HashSet::from([0, 1, 2, 3])), 0);
+        assert_eq!(graph.cut_weight(&HashSet::from([3])), 3);
+    }
+
+    #[test]
+    fn test_all_weighted_edges() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+
+        assert_eq!(
+            graph.all_weighted_edges().collect::<Vec<_>>(),
+            vec![(0, &1, &1), (0, &2, &2), (1, &3, &1), (2, &3, &2)]
+        );
+        assert_eq!(
+            graph.all_edges().collect::<Vec<_>>(),
+            vec![(0, &1), (0, &2), (1, &3), (2, &3)]
+        );
+    }
+
+    #[test]
+    fn test_get_weight() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            vec![
+                String::from("node-1"),
+                String::from("node-2"),
+                String::from("node-3"),
+                String::from("node-4"),
+            ]
+        );
+        graph.add_weighted_connection(&0, &1, 1);
+        graph.add_weighted_connection(&1, &3, 1);
+        graph.add_weighted_connection(&0, &2, 2);
+        graph.add_weighted_connection(&2, &3, 2);
+        graph.add_weighted_connection(&0, &2, 7);
+
+        assert_eq!(graph.get_weight(&1, &3), Some(&1));
+        assert_eq!(graph.get_weight(&0, &2), Some(&2));
+        assert_eq!(graph.get_weight(&3, &1), None);
+        assert_eq!(graph.get_weight(&9, &1), None);
+    }
+
+    #[test]
+    fn test_approx_tsp_tour() {
+        let mut graph: AdjacencyListGraph<u16, String, i32> = AdjacencyListGraph::new(
+            (1..=4).map(|index| format!("node-{}", index)).collect()
+        );
+        for (a, b, weight) in [(0, 1, 1), (0, 2, 4), (0, 3, 3), (1, 2, 2), (1, 3, 5), (2, 3, 1)] {
+            graph.add_weighted_connection(&a, &b, weight);
+            graph.add_weighted_connection(&b, &a, weight);
+        }
+
+        let (tour, cost) = graph.approx_tsp_tour(&0).unwrap();
+        assert_eq!(tour, vec![0, 1, 2, 3, 0]);
+        assert_eq!(cost, 7);
+        let mut visited = tour[..4].to_vec();
+        visited.sort();
+        assert_eq!(visited, vec![0, 1, 2, 3]);
+
+        let (tour, cost) = graph.approx_tsp_tour(&3).unwrap();
+        assert_eq!(tour, vec![3, 2, 1, 0, 3]);
+        assert_eq!(cost, 7);
+
+        graph.insert(String::from("node-5"));
+        assert_eq!(graph.approx_tsp_tour(&0), None);
+
+        let single: AdjacencyListGraph<u16, String, i32> =
+            AdjacencyListGraph::new(vec![String::from("node-1")]);
+        assert_eq!(single.approx_tsp_tour(&0), Some((vec![0], 0)));
+        assert_eq!(single.approx_tsp_tour(&1), None);
+    }
+}